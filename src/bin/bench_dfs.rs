@@ -0,0 +1,122 @@
+use std::env;
+use std::process;
+use std::time::{Duration, Instant};
+use sudoku_solver_ed::app;
+use sudoku_solver_ed::error::Error;
+use sudoku_solver_ed::grid::{Cell, Regions, Shape, Table};
+use sudoku_solver_ed::solver::{solve_dfs_single, solve_dlx, solve_propagating_dfs};
+
+type Solver = fn(Table) -> Result<Table, Error>;
+
+const SOLVERS: [(&str, Solver); 3] = [
+    ("solve_dfs_single", solve_dfs_single),
+    ("solve_propagating_dfs", solve_propagating_dfs),
+    ("solve_dlx", solve_dlx),
+];
+
+// Whether `answer` is a full solution keeping every given of `puzzle`.
+// Puzzles with several solutions can be answered differently by each solver.
+fn completes(puzzle: &Table, answer: &Table) -> bool {
+    answer.solved()
+        && puzzle.grid.iter().zip(answer.grid.iter()).all(|(p, a)| {
+            p.iter()
+                .zip(a.iter())
+                .all(|(&pc, &ac)| pc == Cell::Empty || pc == ac)
+        })
+}
+
+const USAGE: &str = "Usage: bench_dfs [-b ROWSxCOLS | -r REGIONS] [INPUT]";
+
+struct Args {
+    input: Option<String>,
+    shape: Option<Shape>,
+    regions: Option<Regions>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut res = Args {
+        input: None,
+        shape: None,
+        regions: None,
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-b" | "--box" => {
+                let v = args.next().ok_or("Missing value for -b.")?;
+                res.shape = Some(app::parse_shape(&v).ok_or(format!("Invalid box shape: {}.", v))?);
+            }
+            "-r" | "--regions" => {
+                let v = args.next().ok_or("Missing value for -r.")?;
+                res.regions = Some(app::read_regions(&v)?);
+            }
+            _ if res.input.is_none() && (arg == "-" || !arg.starts_with('-')) => {
+                res.input = Some(arg);
+            }
+            _ => return Err(format!("Unexpected argument: {}.", arg)),
+        }
+    }
+    if res.shape.is_some() && res.regions.is_some() {
+        return Err("-b can't be combined with -r.".to_string());
+    }
+    Ok(res)
+}
+
+fn main() {
+    let args = parse_args().unwrap_or_else(|e| {
+        eprintln!("Error: {}\n{}", e, USAGE);
+        process::exit(2);
+    });
+    let input = app::read_input(args.input.as_deref()).unwrap_or_else(|e| {
+        eprintln!("Error: couldn't read input: {}.", e);
+        process::exit(1);
+    });
+    let puzzles = match (&args.regions, args.shape) {
+        (Some(regions), _) => app::puzzles_from_lines_with_regions(&input, regions),
+        (None, Some(shape)) => app::puzzles_from_lines_with_shape(&input, shape),
+        (None, None) => app::puzzles_from_lines(&input),
+    };
+    let puzzles: Vec<Table> = puzzles.into_iter().filter_map(|(_, p)| p.ok()).collect();
+    println!("Benchmarking {} puzzles.", puzzles.len());
+
+    let mut answered: Vec<bool> = vec![];
+    for (name, solve) in SOLVERS {
+        let mut total = Duration::ZERO;
+        let mut slowest = Duration::ZERO;
+        let mut solved = 0;
+        let mut wrong = 0;
+        let mut res = vec![];
+        for t in puzzles.iter() {
+            let start = Instant::now();
+            let t_res = solve(t.clone()).ok();
+            let spent_time = start.elapsed();
+            total += spent_time;
+            slowest = slowest.max(spent_time);
+            match &t_res {
+                Some(answer) if completes(t, answer) => solved += 1,
+                Some(_) => wrong += 1,
+                None => {}
+            }
+            res.push(t_res.is_some());
+        }
+        println!(
+            "{:>22}: solved {}, total {:.3} s, mean {:.3} ms, slowest {:.3} ms",
+            name,
+            solved,
+            total.as_secs_f64(),
+            total.as_secs_f64() * 1000.0 / puzzles.len().max(1) as f64,
+            slowest.as_secs_f64() * 1000.0
+        );
+        if wrong > 0 {
+            println!("Warning: {} gave {} wrong answers.", name, wrong);
+        }
+        if answered.is_empty() {
+            answered = res;
+        } else if answered != res {
+            println!(
+                "Warning: {} solves other puzzles than {}.",
+                name, SOLVERS[0].0
+            );
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use std::env;
+use std::io::{self, BufWriter, Write};
+use std::process;
+use std::time::{Duration, Instant};
+use sudoku_solver_ed::error::Error;
+use sudoku_solver_ed::generator::{generate, Settings, Symmetry};
+use sudoku_solver_ed::grid::Table;
+use sudoku_solver_ed::solver::logic::Technique;
+
+const USAGE: &str = "Usage: generate [-n COUNT] [-c CLUES] [-s SEED] [-y SYMMETRY] [-m]
+                [-r MIN:MAX] [-t TECHNIQUE] [-a ATTEMPTS] [-b SECONDS]
+
+Writes COUNT 9x9 puzzles with a unique solution to stdout, one per line.
+Puzzle i uses seed SEED + i, clues are removed down to CLUES if possible.
+SYMMETRY is one of none (default), rot180, rot90, horizontal, vertical,
+diagonal, antidiagonal or dihedral. With -m every puzzle is minimal,
+even if that takes it below CLUES. It can't be combined with -y.
+Puzzles can be limited to an SE rating range (-r 7.0:8.0) and to needing
+TECHNIQUE (-t x-wing) with nothing harder. Each puzzle then gets ATTEMPTS
+tries (1000 by default) and SECONDS to be found, a line `failed: ...` is
+written when either runs out.";
+
+struct Args {
+    count: usize,
+    settings: Settings,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut res = Args {
+        count: 1,
+        settings: Settings::default(),
+    };
+    let settings = &mut res.settings;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-n" | "--count" => {
+                let v = args.next().ok_or("Missing value for -n.")?;
+                res.count = v.parse().map_err(|_| format!("Invalid count: {}.", v))?;
+            }
+            "-c" | "--clues" => {
+                let v = args.next().ok_or("Missing value for -c.")?;
+                // The generator only makes standard 9x9 grids.
+                let cells = Table::default().size().pow(2);
+                settings.clues = match v.parse() {
+                    Ok(n) if n <= cells => n,
+                    _ => return Err(format!("Invalid clue count: {}.", v)),
+                };
+            }
+            "-s" | "--seed" => {
+                let v = args.next().ok_or("Missing value for -s.")?;
+                settings.seed = v.parse().map_err(|_| format!("Invalid seed: {}.", v))?;
+            }
+            "-y" | "--symmetry" => {
+                let v = args.next().ok_or("Missing value for -y.")?;
+                settings.symmetry = *Symmetry::ALL
+                    .iter()
+                    .find(|s| s.name() == v)
+                    .ok_or(format!("Unknown symmetry: {}.", v))?;
+            }
+            "-m" | "--minimal" => settings.minimal = true,
+            "-r" | "--rating" => {
+                let v = args.next().ok_or("Missing value for -r.")?;
+                let range = v.split_once(':').and_then(|(lo, hi)| {
+                    let (lo, hi): (f32, f32) = (lo.parse().ok()?, hi.parse().ok()?);
+                    Some(lo..=hi)
+                });
+                settings.rating = Some(range.ok_or(format!("Invalid rating range: {}.", v))?);
+            }
+            "-t" | "--technique" => {
+                let v = args.next().ok_or("Missing value for -t.")?;
+                let t = Technique::ALL
+                    .iter()
+                    .find(|t| t.name().eq_ignore_ascii_case(&v))
+                    .ok_or(format!("Unknown technique: {}.", v))?;
+                settings.hardest = Some(*t);
+            }
+            "-a" | "--attempts" => {
+                let v = args.next().ok_or("Missing value for -a.")?;
+                settings.max_rejected = match v.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(format!("Invalid attempt count: {}.", v)),
+                };
+            }
+            "-b" | "--budget" => {
+                let v = args.next().ok_or("Missing value for -b.")?;
+                let budget = v
+                    .parse()
+                    .ok()
+                    .filter(|&secs: &f32| secs > 0.0)
+                    .and_then(|secs| Duration::try_from_secs_f32(secs).ok());
+                settings.time_budget = Some(budget.ok_or(format!("Invalid budget: {}.", v))?);
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            _ => return Err(format!("Unexpected argument: {}.", arg)),
+        }
+    }
+    if settings.minimal && settings.symmetry != Symmetry::None {
+        return Err("-m can't be combined with -y.".to_string());
+    }
+    Ok(res)
+}
+
+fn main() {
+    let args = parse_args().unwrap_or_else(|e| {
+        eprintln!("Error: {}\n\n{}", e, USAGE);
+        process::exit(2);
+    });
+
+    let start = Instant::now();
+    let mut out = BufWriter::new(io::stdout().lock());
+    let (mut generated, mut clues, mut rejected) = (0, 0, 0);
+    for i in 0..args.count {
+        let settings = Settings {
+            seed: args.settings.seed.wrapping_add(i as u64),
+            ..args.settings.clone()
+        };
+        let written = match generate(&settings) {
+            Ok(puzzle) => {
+                generated += 1;
+                clues += puzzle.clues();
+                rejected += puzzle.rejected;
+                match &puzzle.rating {
+                    Some(rating) => writeln!(out, "{} {}", puzzle.table.to_line(), rating),
+                    None => writeln!(out, "{}", puzzle.table.to_line()),
+                }
+            }
+            Err(e) => {
+                if let Error::NoMatch { rejected: n } = e {
+                    rejected += n;
+                }
+                writeln!(out, "failed: {}", e)
+            }
+        };
+        if let Err(e) = written {
+            eprintln!("Error: couldn't write output: {}.", e);
+            process::exit(1);
+        }
+    }
+    if let Err(e) = out.flush() {
+        eprintln!("Error: couldn't write output: {}.", e);
+        process::exit(1);
+    }
+
+    eprintln!("Base seed: {}.", args.settings.seed);
+    eprintln!(
+        "{} of {} puzzles with {:.1} clues on average in {} seconds, {} rejected.",
+        generated,
+        args.count,
+        clues as f32 / generated.max(1) as f32,
+        start.elapsed().as_secs_f32(),
+        rejected
+    );
+}
@@ -1,3 +1,187 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Instant;
+use sudoku_solver_ed::app;
+use sudoku_solver_ed::error::Error;
+use sudoku_solver_ed::grid::{Regions, Shape, Table};
+use sudoku_solver_ed::solver::solve_dfs_single;
+
+const USAGE: &str = "Usage: multi_dfs [-j THREADS] [-o OUTPUT] [-b ROWSxCOLS | -r REGIONS]
+                 [INPUT]
+
+Solves one puzzle per line (81 cells for 9x9, 36 for 6x6 and so on up
+to 25x25, `0` or `.` for empty, `A` to `P` for 10 to 25) from INPUT, or
+from stdin when INPUT is omitted or `-`. Boxes get the default shape for
+the size (2x3 for 6x6) unless -b sets it, like -b 3x2 for tall boxes.
+Jigsaw puzzles take the regions from the file REGIONS instead, one row
+per line with regions labelled like digits from `1`.
+Solutions are written in input order to OUTPUT (stdout by default), the
+run summary to stderr.";
+
+enum Outcome {
+    Solved(Table),
+    Unsolvable(Error),
+    Malformed(Error),
+}
+
+struct Args {
+    threads: usize,
+    input: Option<String>,
+    output: Option<String>,
+    shape: Option<Shape>,
+    regions: Option<Regions>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut res = Args {
+        threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        input: None,
+        output: None,
+        shape: None,
+        regions: None,
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-j" | "--threads" => {
+                let v = args.next().ok_or("Missing value for -j.")?;
+                res.threads = match v.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(format!("Invalid thread count: {}.", v)),
+                };
+            }
+            "-o" | "--output" => {
+                res.output = Some(args.next().ok_or("Missing value for -o.")?);
+            }
+            "-b" | "--box" => {
+                let v = args.next().ok_or("Missing value for -b.")?;
+                res.shape = Some(app::parse_shape(&v).ok_or(format!("Invalid box shape: {}.", v))?);
+            }
+            "-r" | "--regions" => {
+                let v = args.next().ok_or("Missing value for -r.")?;
+                res.regions = Some(app::read_regions(&v)?);
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            _ if res.input.is_none() && (arg == "-" || !arg.starts_with('-')) => {
+                res.input = Some(arg);
+            }
+            _ => return Err(format!("Unexpected argument: {}.", arg)),
+        }
+    }
+    if res.shape.is_some() && res.regions.is_some() {
+        return Err("-b can't be combined with -r.".to_string());
+    }
+    Ok(res)
+}
+
+fn solve(puzzle: Result<Table, Error>) -> Outcome {
+    match puzzle {
+        Err(e) => Outcome::Malformed(e),
+        Ok(t) => match solve_dfs_single(t) {
+            Ok(t_res) => Outcome::Solved(t_res),
+            Err(e) => Outcome::Unsolvable(e),
+        },
+    }
+}
+
 fn main() {
-    println!("Hello, world!");
+    let args = parse_args().unwrap_or_else(|e| {
+        eprintln!("Error: {}\n\n{}", e, USAGE);
+        process::exit(2);
+    });
+    let input = app::read_input(args.input.as_deref()).unwrap_or_else(|e| {
+        eprintln!("Error: couldn't read input: {}.", e);
+        process::exit(1);
+    });
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => match File::create(path) {
+            Ok(f) => Box::new(BufWriter::new(f)),
+            Err(e) => {
+                eprintln!("Error: couldn't create {}: {}.", path, e);
+                process::exit(1);
+            }
+        },
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    let start = Instant::now();
+    let puzzles = match (&args.regions, args.shape) {
+        (Some(regions), _) => app::puzzles_from_lines_with_regions(&input, regions),
+        (None, Some(shape)) => app::puzzles_from_lines_with_shape(&input, shape),
+        (None, None) => app::puzzles_from_lines(&input),
+    };
+    let total = puzzles.len();
+    let jobs: Vec<_> = puzzles.into_iter().map(Some).map(Mutex::new).collect();
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel();
+
+    let (mut solved, mut unsolvable, mut malformed) = (0, 0, 0);
+    let written = thread::scope(|s| {
+        for _ in 0..args.threads.min(total.max(1)) {
+            let tx = tx.clone();
+            let (jobs, next) = (&jobs, &next);
+            s.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= jobs.len() {
+                    break;
+                }
+                let (_line, puzzle) = jobs[i].lock().unwrap().take().unwrap();
+                if tx.send((i, solve(puzzle))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        // Results arrive in completion order, hold them back until their turn.
+        let mut pending = BTreeMap::new();
+        let mut expected = 0;
+        for (i, outcome) in rx {
+            pending.insert(i, outcome);
+            while let Some(outcome) = pending.remove(&expected) {
+                match outcome {
+                    Outcome::Solved(t) => {
+                        solved += 1;
+                        writeln!(out, "{}", t.to_line())?;
+                    }
+                    Outcome::Unsolvable(e) => {
+                        unsolvable += 1;
+                        writeln!(out, "unsolvable: {}", e)?;
+                    }
+                    Outcome::Malformed(e) => {
+                        malformed += 1;
+                        writeln!(out, "malformed: {}", e)?;
+                    }
+                }
+                expected += 1;
+            }
+        }
+        out.flush()
+    });
+    if let Err(e) = written {
+        eprintln!("Error: couldn't write output: {}.", e);
+        process::exit(1);
+    }
+
+    let spent_time = start.elapsed().as_secs_f32();
+    eprintln!(
+        "Solved: {}, unsolvable: {}, malformed: {}.",
+        solved, unsolvable, malformed
+    );
+    eprintln!(
+        "{} puzzles in {} seconds ({:.1} puzzles/sec) on {} threads.",
+        total,
+        spent_time,
+        total as f32 / spent_time.max(f32::EPSILON),
+        args.threads
+    );
 }
@@ -1,3 +1,101 @@
+use rand::{thread_rng, Rng};
+use std::env;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+use sudoku_solver_ed::app;
+use sudoku_solver_ed::grid::Table;
+use sudoku_solver_ed::solver::solve_randomized_dfs_seeded;
+
+const USAGE: &str = "Usage: multi_randomized_dfs [-j THREADS] [-s SEED]";
+
+struct Args {
+    threads: usize,
+    seed: u64,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut res = Args {
+        threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        seed: thread_rng().gen(),
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-j" | "--threads" => {
+                let v = args.next().ok_or("Missing value for -j.")?;
+                res.threads = match v.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(format!("Invalid thread count: {}.", v)),
+                };
+            }
+            "-s" | "--seed" => {
+                let v = args.next().ok_or("Missing value for -s.")?;
+                res.seed = v.parse().map_err(|_| format!("Invalid seed: {}.", v))?;
+            }
+            _ => return Err(format!("Unexpected argument: {}.", arg)),
+        }
+    }
+    Ok(res)
+}
+
 fn main() {
-    println!("Hello, world!");
+    let args = parse_args().unwrap_or_else(|e| {
+        eprintln!("Error: {}\n{}", e, USAGE);
+        process::exit(2);
+    });
+
+    println!("Write sudoku puzzle to solve:");
+    let t = app::table_from_stdin();
+    if app::report_conflicts(&t) {
+        return;
+    }
+
+    let start = Instant::now();
+    let cancel = AtomicBool::new(false);
+    let winner: Mutex<Option<(usize, Table)>> = Mutex::new(None);
+
+    // Every thread searches with its own seed, the first one to finish
+    // cancels the others.
+    let nodes: Vec<u64> = thread::scope(|s| {
+        let handles: Vec<_> = (0..args.threads)
+            .map(|id| {
+                let (t, cancel, winner) = (&t, &cancel, &winner);
+                let seed = args.seed.wrapping_add(id as u64);
+                s.spawn(move || {
+                    let (res, nodes) = solve_randomized_dfs_seeded(t.clone(), seed, cancel);
+                    if let Ok(table) = res {
+                        if !cancel.swap(true, Ordering::Relaxed) {
+                            *winner.lock().unwrap() = Some((id, table));
+                        }
+                    }
+                    nodes
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let spent_time = start.elapsed().as_secs_f32();
+    let winner = winner.into_inner().unwrap();
+    match &winner {
+        Some((_, table)) => {
+            println!("Solution found in {} seconds:", spent_time);
+            table.print();
+        }
+        None => {
+            println!("Sudoku has no solution.");
+        }
+    }
+    println!("Base seed: {}.", args.seed);
+    for (id, n) in nodes.iter().enumerate() {
+        let mark = match winner {
+            Some((w, _)) if w == id => " (winner)",
+            _ => "",
+        };
+        println!("Thread {}: {} nodes{}.", id, n, mark);
+    }
+    println!("Total: {} nodes.", nodes.iter().sum::<u64>());
 }
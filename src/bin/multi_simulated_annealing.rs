@@ -1,3 +1,120 @@
+use std::env;
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+use sudoku_solver_ed::app;
+use sudoku_solver_ed::error::Error;
+use sudoku_solver_ed::grid::Table;
+use sudoku_solver_ed::solver::solve_simulated_annealing_chain;
+
+const MAX_ATTEMPTS: u8 = 65;
+
+struct Winner {
+    thread: usize,
+    attempt: u8,
+    iters: u32,
+    table: Table,
+}
+
+fn threads_from_args() -> Result<usize, String> {
+    let mut res = thread::available_parallelism().map_or(1, |n| n.get());
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-j" | "--threads" => {
+                let v = args.next().ok_or("Missing value for -j.")?;
+                res = match v.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(format!("Invalid thread count: {}.", v)),
+                };
+            }
+            _ => return Err(format!("Unexpected argument: {}.", arg)),
+        }
+    }
+    Ok(res)
+}
+
 fn main() {
-    println!("Hello, anneals!");
+    let threads = threads_from_args().unwrap_or_else(|e| {
+        eprintln!(
+            "Error: {}\nUsage: multi_simulated_annealing [-j THREADS]",
+            e
+        );
+        process::exit(2);
+    });
+
+    println!("Write sudoku puzzle to solve:");
+    let t = app::table_from_stdin();
+    if app::report_conflicts(&t) {
+        return;
+    }
+
+    let start = Instant::now();
+    let cancel = AtomicBool::new(false);
+    let total_iters = AtomicU64::new(0);
+    let winner: Mutex<Option<Winner>> = Mutex::new(None);
+    let failure: Mutex<Option<Error>> = Mutex::new(None);
+
+    thread::scope(|s| {
+        for id in 0..threads {
+            let (t, cancel, total_iters) = (&t, &cancel, &total_iters);
+            let (winner, failure) = (&winner, &failure);
+            s.spawn(move || {
+                for attempt in 0..MAX_ATTEMPTS {
+                    let (res, iters) = solve_simulated_annealing_chain(t.clone(), cancel);
+                    total_iters.fetch_add(iters as u64, Ordering::Relaxed);
+                    match res {
+                        // Only the first chain to finish gets to report.
+                        Ok(table) => {
+                            if !cancel.swap(true, Ordering::Relaxed) {
+                                *winner.lock().unwrap() = Some(Winner {
+                                    thread: id,
+                                    attempt,
+                                    iters,
+                                    table,
+                                });
+                            }
+                        }
+                        Err(Error::BudgetExhausted { .. }) | Err(Error::Cancelled) => {}
+                        // Restarting won't help an invalid puzzle.
+                        Err(e) => {
+                            if !cancel.swap(true, Ordering::Relaxed) {
+                                *failure.lock().unwrap() = Some(e);
+                            }
+                        }
+                    }
+                    if cancel.load(Ordering::Relaxed) {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    let spent_time = start.elapsed().as_secs_f32();
+    let total_iters = total_iters.load(Ordering::Relaxed);
+    match winner.into_inner().unwrap() {
+        Some(w) => {
+            println!("Solution found in {} seconds:", spent_time);
+            w.table.print();
+            println!(
+                "Chain #{} of thread {} won after {} iterations ({} iterations over all chains).",
+                w.attempt as usize + 1,
+                w.thread,
+                w.iters,
+                total_iters
+            );
+        }
+        None => match failure.into_inner().unwrap() {
+            Some(e) => println!("Sudoku has no solution: {}.", e),
+            None => println!(
+                "Annealing didn't find solution in {} chains on {} threads ({} iterations).",
+                MAX_ATTEMPTS as usize * threads,
+                threads,
+                total_iters
+            ),
+        },
+    }
 }
@@ -5,6 +5,9 @@ use sudoku_solver_ed::solver::solve_randomized_dfs_single;
 fn main() {
     println!("Write sudoku puzzle to solve:");
     let t = app::table_from_stdin();
+    if app::report_conflicts(&t) {
+        return;
+    }
 
     let start = Instant::now();
     match solve_randomized_dfs_single(t.clone()) {
@@ -13,8 +16,8 @@ fn main() {
             println!("Solution found in {} seconds:", spent_time);
             t_res.print();
         }
-        Err(_e) => {
-            println!("Sudoku has no solution.");
+        Err(e) => {
+            println!("Sudoku has no solution: {}.", e);
         }
     }
 }
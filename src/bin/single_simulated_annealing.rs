@@ -1,5 +1,6 @@
 use std::time::Instant;
 use sudoku_solver_ed::app;
+use sudoku_solver_ed::error::Error;
 use sudoku_solver_ed::solver::solve_simulated_annealing_single;
 
 const MAX_ATTEMPTS: u8 = 65;
@@ -7,6 +8,9 @@ const MAX_ATTEMPTS: u8 = 65;
 fn main() {
     println!("Write sudoku puzzle to solve:");
     let t = app::table_from_stdin();
+    if app::report_conflicts(&t) {
+        return;
+    }
 
     let start = Instant::now();
     for i in 0..MAX_ATTEMPTS {
@@ -17,12 +21,16 @@ fn main() {
                 t_res.print();
                 break;
             }
-            Err(_e) => {
+            Err(Error::BudgetExhausted { .. }) => {
                 println!(
                     "Annealing didn't find solution, attempts left: {}.",
                     MAX_ATTEMPTS - i - 1
                 );
             }
+            Err(e) => {
+                println!("Sudoku has no solution: {}.", e);
+                break;
+            }
         }
     }
 }
@@ -0,0 +1,279 @@
+use crate::error::Error;
+use crate::grid::{Cell, Table};
+use crate::rating::{rate, Rating};
+use crate::solver::logic::Technique;
+use crate::solver::{is_unique, random_grid};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng, SeedableRng};
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+// Symmetry of the clue pattern: the clues are unchanged by every map of
+// the group.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum Symmetry {
+    #[default]
+    None,
+    Rotational180,
+    Rotational90,
+    MirrorHorizontal,
+    MirrorVertical,
+    Diagonal,
+    AntiDiagonal,
+    Dihedral,
+}
+
+type Map = fn((usize, usize)) -> (usize, usize);
+
+const IDENTITY: Map = |(r, c)| (r, c);
+const ROTATE_90: Map = |(r, c)| (c, 8 - r);
+const ROTATE_180: Map = |(r, c)| (8 - r, 8 - c);
+const ROTATE_270: Map = |(r, c)| (8 - c, r);
+// Across the horizontal middle line, top and bottom swap.
+const FLIP_ROWS: Map = |(r, c)| (8 - r, c);
+const FLIP_COLS: Map = |(r, c)| (r, 8 - c);
+const TRANSPOSE: Map = |(r, c)| (c, r);
+const ANTI_TRANSPOSE: Map = |(r, c)| (8 - c, 8 - r);
+
+impl Symmetry {
+    pub const ALL: &'static [Symmetry] = &[
+        Symmetry::None,
+        Symmetry::Rotational180,
+        Symmetry::Rotational90,
+        Symmetry::MirrorHorizontal,
+        Symmetry::MirrorVertical,
+        Symmetry::Diagonal,
+        Symmetry::AntiDiagonal,
+        Symmetry::Dihedral,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Symmetry::None => "none",
+            Symmetry::Rotational180 => "rot180",
+            Symmetry::Rotational90 => "rot90",
+            Symmetry::MirrorHorizontal => "horizontal",
+            Symmetry::MirrorVertical => "vertical",
+            Symmetry::Diagonal => "diagonal",
+            Symmetry::AntiDiagonal => "antidiagonal",
+            Symmetry::Dihedral => "dihedral",
+        }
+    }
+
+    fn maps(&self) -> &'static [Map] {
+        match self {
+            Symmetry::None => &[IDENTITY],
+            Symmetry::Rotational180 => &[IDENTITY, ROTATE_180],
+            Symmetry::Rotational90 => &[IDENTITY, ROTATE_90, ROTATE_180, ROTATE_270],
+            Symmetry::MirrorHorizontal => &[IDENTITY, FLIP_ROWS],
+            Symmetry::MirrorVertical => &[IDENTITY, FLIP_COLS],
+            Symmetry::Diagonal => &[IDENTITY, TRANSPOSE],
+            Symmetry::AntiDiagonal => &[IDENTITY, ANTI_TRANSPOSE],
+            Symmetry::Dihedral => &[
+                IDENTITY,
+                ROTATE_90,
+                ROTATE_180,
+                ROTATE_270,
+                FLIP_ROWS,
+                FLIP_COLS,
+                TRANSPOSE,
+                ANTI_TRANSPOSE,
+            ],
+        }
+    }
+
+    // Cells a clue at `cell` comes with, itself included.
+    pub fn orbit(&self, cell: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut res: Vec<(usize, usize)> = vec![];
+        for map in self.maps() {
+            let image = map(cell);
+            if !res.contains(&image) {
+                res.push(image);
+            }
+        }
+        res
+    }
+
+    // Every cell of the grid in exactly one orbit.
+    pub fn orbits(&self) -> Vec<Vec<(usize, usize)>> {
+        let mut seen = [[false; 9]; 9];
+        let mut res = vec![];
+        for r in 0..9 {
+            for c in 0..9 {
+                if !seen[r][c] {
+                    let orbit = self.orbit((r, c));
+                    for &(r2, c2) in orbit.iter() {
+                        seen[r2][c2] = true;
+                    }
+                    res.push(orbit);
+                }
+            }
+        }
+        res
+    }
+
+    pub fn matches(&self, t: &Table) -> bool {
+        self.orbits().iter().all(|orbit| {
+            let given = |&(r, c): &(usize, usize)| t.grid[r][c] != Cell::Empty;
+            orbit.iter().all(given) || !orbit.iter().any(given)
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    // Clue removal stops here, or earlier when no clue can go without
+    // losing uniqueness.
+    pub clues: usize,
+    // Everything random is drawn from it, so equal settings give equal
+    // puzzles.
+    pub seed: u64,
+    pub symmetry: Symmetry,
+    // Removes every clue that can go once the orbits are done, even below
+    // `clues`, so puzzles are always minimal. Single clues can't go
+    // without breaking a symmetry, so it needs Symmetry::None.
+    pub minimal: bool,
+    // Accepted SE scores, see `rating::rate`.
+    pub rating: Option<RangeInclusive<f32>>,
+    // The technique a puzzle must need, with nothing harder.
+    pub hardest: Option<Technique>,
+    // Gives up on the filters above after this many rejected puzzles, or
+    // after this long.
+    pub max_rejected: u64,
+    pub time_budget: Option<Duration>,
+}
+
+pub const MAX_REJECTED: u64 = 1000;
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            clues: 0,
+            seed: thread_rng().gen(),
+            symmetry: Symmetry::None,
+            minimal: false,
+            rating: None,
+            hardest: None,
+            max_rejected: MAX_REJECTED,
+            time_budget: None,
+        }
+    }
+}
+
+impl Settings {
+    fn filtered(&self) -> bool {
+        self.rating.is_some() || self.hardest.is_some()
+    }
+
+    fn accepts(&self, rating: &Rating) -> bool {
+        self.rating
+            .as_ref()
+            .is_none_or(|range| range.contains(&rating.score))
+            && self.hardest.is_none_or(|t| rating.hardest == Some(t))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Puzzle {
+    pub table: Table,
+    pub solution: Table,
+    // Only rated when the settings ask for a rating or technique.
+    pub rating: Option<Rating>,
+    // Puzzles thrown away for missing the rating or technique.
+    pub rejected: u64,
+}
+
+impl Puzzle {
+    pub fn clues(&self) -> usize {
+        let size = self.table.size();
+        size * size - self.table.empty_cells().len()
+    }
+}
+
+// Generates 9x9 puzzles until one passes the rating and technique filters.
+// Fails with NoMatch when the rejects or the time budget run out first,
+// and with InvalidSettings for a minimal puzzle with a symmetry.
+pub fn generate(settings: &Settings) -> Result<Puzzle, Error> {
+    if settings.minimal && settings.symmetry != Symmetry::None {
+        let reason = format!(
+            "minimal puzzles can't keep {} symmetry",
+            settings.symmetry.name()
+        );
+        return Err(Error::InvalidSettings { reason });
+    }
+    let start = Instant::now();
+    let mut rng = StdRng::seed_from_u64(settings.seed);
+    let mut rejected = 0;
+    loop {
+        let (table, solution) = dig(settings, &mut rng);
+        if !settings.filtered() {
+            return Ok(Puzzle {
+                table,
+                solution,
+                rating: None,
+                rejected,
+            });
+        }
+        let rating = rate(&table)?;
+        if settings.accepts(&rating) {
+            return Ok(Puzzle {
+                table,
+                solution,
+                rating: Some(rating),
+                rejected,
+            });
+        }
+        rejected += 1;
+        if rejected >= settings.max_rejected
+            || settings
+                .time_budget
+                .is_some_and(|budget| start.elapsed() >= budget)
+        {
+            return Err(Error::NoMatch { rejected });
+        }
+    }
+}
+
+// Digs clues out of a random complete grid a whole symmetry orbit at a
+// time, in random order, keeping every removal that leaves the solution
+// unique. Orbits that would go below the target are skipped.
+fn dig(settings: &Settings, rng: &mut StdRng) -> (Table, Table) {
+    let solution = random_grid(rng);
+    let mut table = solution.clone();
+    let mut orbits = settings.symmetry.orbits();
+    orbits.shuffle(rng);
+    let size = solution.size();
+    let mut clues = size * size;
+    for orbit in orbits {
+        if clues <= settings.clues {
+            break;
+        }
+        if clues - orbit.len() < settings.clues {
+            continue;
+        }
+        for &(r, c) in orbit.iter() {
+            table.grid[r][c] = Cell::Empty;
+        }
+        if is_unique(&table) {
+            clues -= orbit.len();
+        } else {
+            for &(r, c) in orbit.iter() {
+                table.grid[r][c] = solution.grid[r][c];
+            }
+        }
+    }
+    if settings.minimal {
+        let mut cells: Vec<(usize, usize)> =
+            (0..size * size).map(|i| (i / size, i % size)).collect();
+        cells.shuffle(rng);
+        for (r, c) in cells {
+            let given = table.grid[r][c];
+            table.grid[r][c] = Cell::Empty;
+            if !is_unique(&table) {
+                table.grid[r][c] = given;
+            }
+        }
+    }
+    (table, solution)
+}
@@ -1,45 +1,474 @@
+pub mod error {
+    use crate::grid::Conflict;
+    use std::fmt;
+    use std::io;
+
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub enum Error {
+        Io(io::ErrorKind),
+        // 1-based position of the offending token, which is empty when the
+        // input ended too early.
+        Parse {
+            line: usize,
+            column: usize,
+            token: String,
+        },
+        // Givens that clash with each other.
+        Contradiction {
+            conflicts: Vec<Conflict>,
+        },
+        NoSolution,
+        // More than one solution where exactly one was required.
+        NotUnique,
+        BudgetExhausted {
+            iterations: u64,
+        },
+        // Generator settings that can't be met together.
+        InvalidSettings {
+            reason: String,
+        },
+        // The generator threw away this many puzzles without finding one
+        // that passes its filters.
+        NoMatch {
+            rejected: u64,
+        },
+        Cancelled,
+        // A kind of grid a solver can't handle, such as "16x16" or "jigsaw".
+        Unsupported {
+            variant: String,
+        },
+        // A jigsaw region, numbered from 1, with the wrong number of cells.
+        RegionSize {
+            region: usize,
+            cells: usize,
+        },
+        // A jigsaw region whose cells don't all touch.
+        RegionNotConnected {
+            region: usize,
+        },
+    }
+
+    impl Error {
+        pub(crate) fn parse(line: usize, column: usize, token: &str) -> Error {
+            Error::Parse {
+                line,
+                column,
+                token: token.to_string(),
+            }
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Error::Io(kind) => write!(f, "couldn't read input ({})", kind),
+                Error::Parse {
+                    line,
+                    column,
+                    token,
+                } if token.is_empty() => {
+                    write!(
+                        f,
+                        "line {}, column {}: unexpected end of line",
+                        line, column
+                    )
+                }
+                Error::Parse {
+                    line,
+                    column,
+                    token,
+                } => write!(
+                    f,
+                    "line {}, column {}: unexpected `{}`",
+                    line, column, token
+                ),
+                Error::Contradiction { conflicts } => {
+                    write!(f, "givens contradict each other")?;
+                    for (i, conflict) in conflicts.iter().enumerate() {
+                        let sep = if i == 0 { ": " } else { "; " };
+                        write!(f, "{}{}", sep, conflict)?;
+                    }
+                    Ok(())
+                }
+                Error::NoSolution => write!(f, "no solution found"),
+                Error::NotUnique => write!(f, "more than one solution"),
+                Error::BudgetExhausted { iterations } => {
+                    write!(f, "gave up after {} iterations", iterations)
+                }
+                Error::InvalidSettings { reason } => write!(f, "invalid settings: {}", reason),
+                Error::NoMatch { rejected } => {
+                    write!(f, "no match after {} rejected puzzles", rejected)
+                }
+                Error::Cancelled => write!(f, "cancelled"),
+                Error::Unsupported { variant } => {
+                    write!(f, "{} grids are not supported", variant)
+                }
+                Error::RegionSize { region, cells } => {
+                    write!(f, "region {} has {} cells", region, cells)
+                }
+                Error::RegionNotConnected { region } => {
+                    write!(f, "region {} is not connected", region)
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for Error {}
+}
+
 pub mod grid {
+    use crate::error::Error;
+    use std::fmt;
     use std::io;
+    use std::sync::Arc;
 
-    #[derive(Clone, Copy, Eq, PartialEq, Debug)]
+    #[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
     pub enum Cell {
         Digit(u8),
+        #[default]
         Empty,
     }
 
-    #[derive(Debug, Eq, PartialEq)]
+    #[derive(Clone, Debug, Eq, PartialEq)]
     pub struct Table {
-        pub grid: [[Cell; 9]; 9],
+        pub grid: Vec<Vec<Cell>>,
+        shape: Shape,
+        // Jigsaw regions replacing the boxes of `shape`.
+        regions: Option<Arc<Regions>>,
+        // Sudoku X: both main diagonals hold every digit too.
+        diagonals: bool,
+    }
+
+    // Boxes of `box_rows` by `box_cols` cells, each side from 2 to 5. The
+    // grid has `box_rows * box_cols` rows, columns and digits, so there are
+    // `box_cols` bands and `box_rows` stacks.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub struct Shape {
+        box_rows: usize,
+        box_cols: usize,
+    }
+
+    impl Shape {
+        pub fn new(box_rows: usize, box_cols: usize) -> Shape {
+            assert!((2..=5).contains(&box_rows) && (2..=5).contains(&box_cols));
+            Shape { box_rows, box_cols }
+        }
+
+        pub fn square(box_size: usize) -> Shape {
+            Shape::new(box_size, box_size)
+        }
+
+        // Boxes as close to square as the size allows, wider than high
+        // otherwise: 2x3 for 6x6 grids, 3x4 for 12x12 ones.
+        pub fn for_size(size: usize) -> Option<Shape> {
+            (2..=5)
+                .rev()
+                .filter(|&h| {
+                    h * h <= size && size.is_multiple_of(h) && (2..=5).contains(&(size / h))
+                })
+                .map(|h| Shape::new(h, size / h))
+                .next()
+        }
+
+        // The default shape whose grid is closest to `cells` cells.
+        fn nearest(cells: usize) -> Shape {
+            (4..=25)
+                .filter_map(Shape::for_size)
+                .min_by_key(|shape| (shape.size() * shape.size()).abs_diff(cells))
+                .unwrap()
+        }
+
+        pub fn box_rows(&self) -> usize {
+            self.box_rows
+        }
+
+        pub fn box_cols(&self) -> usize {
+            self.box_cols
+        }
+
+        // Number of rows, columns and digits.
+        pub fn size(&self) -> usize {
+            self.box_rows * self.box_cols
+        }
+
+        pub fn group_of(&self, r: usize, c: usize) -> usize {
+            (r / self.box_rows) * self.box_rows + c / self.box_cols
+        }
+
+        // Cells of group `g`, row by row.
+        pub fn group_cells(&self, g: usize) -> impl Iterator<Item = (usize, usize)> {
+            let (h, w) = (self.box_rows, self.box_cols);
+            let (top, left) = ((g / h) * h, (g % h) * w);
+            (0..h * w).map(move |i| (top + i / w, left + i % w))
+        }
+    }
+
+    impl Default for Shape {
+        fn default() -> Self {
+            Shape::square(3)
+        }
+    }
+
+    // Jigsaw layout: `size` connected regions of `size` cells each take the
+    // place of the boxes.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct Regions {
+        // Region of every cell, row-major.
+        of: Vec<usize>,
+        // Cells of every region, row by row.
+        cells: Vec<Vec<(usize, usize)>>,
+    }
+
+    impl Regions {
+        // `map[r][c]` is the 0-based region of cell (r, c). A map that isn't
+        // square or has a label past the last region is a parse error at
+        // the offending row and column, both 1-based.
+        pub fn new(map: &[Vec<usize>]) -> Result<Regions, Error> {
+            let size = map.len();
+            if size == 0 {
+                return Err(Error::parse(1, 1, ""));
+            }
+            let mut res = Regions {
+                of: Vec::with_capacity(size * size),
+                cells: vec![vec![]; size],
+            };
+            for (r, row) in map.iter().enumerate() {
+                if row.len() < size {
+                    return Err(Error::parse(r + 1, row.len() + 1, ""));
+                }
+                for (c, &g) in row.iter().enumerate() {
+                    if c >= size || g >= size {
+                        return Err(Error::parse(r + 1, c + 1, &g.to_string()));
+                    }
+                    res.of.push(g);
+                    res.cells[g].push((r, c));
+                }
+            }
+            for (g, cells) in res.cells.iter().enumerate() {
+                if cells.len() != size {
+                    let cells = cells.len();
+                    return Err(Error::RegionSize {
+                        region: g + 1,
+                        cells,
+                    });
+                }
+                if !res.connected(g) {
+                    return Err(Error::RegionNotConnected { region: g + 1 });
+                }
+            }
+            Ok(res)
+        }
+
+        // One row per line, regions labelled like digits from `1`. Any
+        // whitespace is skipped, so all the labels may share a line too.
+        pub fn from_text(text: &str) -> Result<Regions, Error> {
+            let count = text.chars().filter(|ch| !ch.is_whitespace()).count();
+            let size = Shape::nearest(count).size();
+            let mut labels = Vec::with_capacity(size * size);
+            let (mut line, mut end) = (1, 1);
+            for (i, row) in text.lines().enumerate() {
+                for (j, ch) in row.chars().enumerate() {
+                    if ch.is_whitespace() {
+                        continue;
+                    }
+                    match symbol_value(ch) {
+                        Some(g)
+                            if labels.len() < size * size && (1..=size).contains(&(g as usize)) =>
+                        {
+                            labels.push(g as usize - 1);
+                        }
+                        _ => return Err(Error::parse(i + 1, j + 1, &ch.to_string())),
+                    }
+                }
+                (line, end) = (i + 1, row.chars().count() + 1);
+            }
+            if labels.len() != size * size {
+                return Err(Error::parse(line, end, ""));
+            }
+            let map: Vec<Vec<usize>> = labels.chunks(size).map(|row| row.to_vec()).collect();
+            Regions::new(&map)
+        }
+
+        pub fn to_text(&self) -> String {
+            let size = self.size();
+            let mut res = String::with_capacity(size * (size + 1));
+            for row in self.of.chunks(size) {
+                res.extend(row.iter().map(|&g| symbol(g as u8 + 1)));
+                res.push('\n');
+            }
+            res
+        }
+
+        pub fn size(&self) -> usize {
+            self.cells.len()
+        }
+
+        pub fn region_of(&self, r: usize, c: usize) -> usize {
+            self.of[r * self.size() + c]
+        }
+
+        pub fn cells(&self, g: usize) -> &[(usize, usize)] {
+            &self.cells[g]
+        }
+
+        // Whether a flood fill from the first cell of region `g` reaches
+        // all of it.
+        fn connected(&self, g: usize) -> bool {
+            let size = self.size();
+            let mut seen = vec![false; size * size];
+            let mut stack = vec![self.cells[g][0]];
+            let mut reached = 0;
+            while let Some((r, c)) = stack.pop() {
+                if seen[r * size + c] {
+                    continue;
+                }
+                seen[r * size + c] = true;
+                reached += 1;
+                let next = [
+                    (r.wrapping_sub(1), c),
+                    (r + 1, c),
+                    (r, c.wrapping_sub(1)),
+                    (r, c + 1),
+                ];
+                for (nr, nc) in next {
+                    if nr < size && nc < size && self.region_of(nr, nc) == g {
+                        stack.push((nr, nc));
+                    }
+                }
+            }
+            reached == size
+        }
+    }
+
+    fn default_shape(size: usize) -> Shape {
+        match Shape::for_size(size) {
+            Some(shape) => shape,
+            None => panic!("no grid has {} rows", size),
+        }
+    }
+
+    // `1`-`9`, then `A` for 10 up to `P` for 25.
+    pub(crate) fn symbol(d: u8) -> char {
+        match d {
+            0 => '.',
+            1..=9 => (b'0' + d) as char,
+            _ => (b'A' + d - 10) as char,
+        }
+    }
+
+    fn symbol_value(ch: char) -> Option<u8> {
+        match ch {
+            '.' => Some(0),
+            '0'..='9' => Some(ch as u8 - b'0'),
+            'A'..='Z' => Some(ch as u8 - b'A' + 10),
+            'a'..='z' => Some(ch as u8 - b'a' + 10),
+            _ => None,
+        }
     }
 
     impl Table {
-        pub fn new(grid: [[Cell; 9]; 9]) -> Table {
-            Table { grid }
+        // Boxes get the default shape for `N`, see `Shape::for_size`.
+        pub fn new<const N: usize>(grid: [[Cell; N]; N]) -> Table {
+            Table {
+                grid: grid.iter().map(|row| row.to_vec()).collect(),
+                shape: default_shape(N),
+                regions: None,
+                diagonals: false,
+            }
+        }
+
+        pub fn with_shape(shape: Shape) -> Table {
+            let size = shape.size();
+            Table {
+                grid: vec![vec![Cell::Empty; size]; size],
+                shape,
+                regions: None,
+                diagonals: false,
+            }
+        }
+
+        pub fn with_box_size(box_size: usize) -> Table {
+            Table::with_shape(Shape::square(box_size))
         }
 
         pub fn from(other: &Table) -> Table {
             other.clone()
         }
 
-        pub fn from_arr(arr: [[u8; 9]; 9]) -> Table {
-            let mut grid: [[Cell; 9]; 9] = Default::default();
-            for i in 0..9 {
-                for j in 0..9 {
-                    assert!(arr[i][j] < 10);
-                    if arr[i][j] == 0 {
-                        grid[i][j] = Cell::Empty;
-                    } else {
-                        grid[i][j] = Cell::Digit(arr[i][j]);
+        pub fn from_arr<const N: usize>(arr: [[u8; N]; N]) -> Table {
+            Table::from_rows(arr.iter().map(|row| row.to_vec()).collect())
+        }
+
+        fn from_rows(rows: Vec<Vec<u8>>) -> Table {
+            let shape = default_shape(rows.len());
+            Table::from_rows_with_shape(rows, shape)
+        }
+
+        fn from_rows_with_shape(rows: Vec<Vec<u8>>, shape: Shape) -> Table {
+            let mut res = Table::with_shape(shape);
+            let size = res.size();
+            for (i, row) in rows.iter().enumerate() {
+                assert_eq!(row.len(), size);
+                for (j, &d) in row.iter().enumerate() {
+                    assert!(d as usize <= size);
+                    if d != 0 {
+                        res.grid[i][j] = Cell::Digit(d);
                     }
                 }
             }
-            Table { grid }
+            res
+        }
+
+        // Number of rows, columns and digits.
+        pub fn size(&self) -> usize {
+            self.shape.size()
+        }
+
+        pub fn shape(&self) -> Shape {
+            self.shape
+        }
+
+        // Keeps the digits, which must still fit in the grid.
+        pub fn set_shape(&mut self, shape: Shape) {
+            assert_eq!(shape.size(), self.size());
+            self.shape = shape;
+        }
+
+        pub fn regions(&self) -> Option<&Regions> {
+            self.regions.as_deref()
+        }
+
+        // Groups follow the regions instead of the boxes from now on, or
+        // the boxes again for None.
+        pub fn set_regions(&mut self, regions: Option<Regions>) {
+            if let Some(regions) = regions.as_ref() {
+                assert_eq!(regions.size(), self.size());
+            }
+            self.regions = regions.map(Arc::new);
+        }
+
+        pub fn diagonals(&self) -> bool {
+            self.diagonals
+        }
+
+        pub fn set_diagonals(&mut self, diagonals: bool) {
+            self.diagonals = diagonals;
+        }
+
+        // An empty grid with the same groups.
+        pub fn blank(&self) -> Table {
+            Table {
+                grid: vec![vec![Cell::Empty; self.size()]; self.size()],
+                shape: self.shape,
+                regions: self.regions.clone(),
+                diagonals: self.diagonals,
+            }
         }
 
         pub fn empty_cells(&self) -> Vec<(usize, usize)> {
             let mut res = vec![];
-            for r in 0..9 {
-                for c in 0..9 {
+            for r in 0..self.size() {
+                for c in 0..self.size() {
                     if self.grid[r][c] == Cell::Empty {
                         res.push((r, c));
                     }
@@ -49,31 +478,13 @@ pub mod grid {
         }
 
         pub fn row_is_ok(&self, r: usize) -> bool {
-            assert!(r < 9);
-            let mut seen = 0;
-            for c in 0..9 {
-                if let Cell::Digit(d) = self.grid[r][c] {
-                    if (seen & (1 << d)) != 0 {
-                        return false;
-                    }
-                    seen |= 1 << d;
-                }
-            }
-            true
+            assert!(r < self.size());
+            self.unit_is_ok(Unit::Row(r))
         }
 
         pub fn col_is_ok(&self, c: usize) -> bool {
-            assert!(c < 9);
-            let mut seen = 0;
-            for r in 0..9 {
-                if let Cell::Digit(d) = self.grid[r][c] {
-                    if (seen & (1 << d)) != 0 {
-                        return false;
-                    }
-                    seen |= 1 << d;
-                }
-            }
-            true
+            assert!(c < self.size());
+            self.unit_is_ok(Unit::Col(c))
         }
 
         // (0,0) | (0,1) | (0,2)
@@ -81,233 +492,942 @@ pub mod grid {
         // (1,0) | (1,1) | (1,2)
         // ------|-------|------
         // (2,0) | (2,1) | (2,2)
+        // `gr` counts bands and `gc` stacks, so with rectangular boxes the
+        // two ranges differ. Jigsaw regions are numbered the same way, see
+        // `region_is_ok`.
         pub fn group_is_ok(&self, gr: usize, gc: usize) -> bool {
-            assert!(gr <= 2 && gc <= 2);
-            let mut seen = 0;
-            for r in (3 * gr)..(3 * gr + 3) {
-                for c in (3 * gc)..(3 * gc + 3) {
-                    if let Cell::Digit(d) = self.grid[r][c] {
-                        if (seen & (1 << d)) != 0 {
-                            return false;
-                        }
-                        seen |= 1 << d;
+            let (h, w) = (self.shape.box_rows, self.shape.box_cols);
+            assert!(gr < w && gc < h);
+            self.unit_is_ok(Unit::Group(gr * h + gc))
+        }
+
+        pub fn region_is_ok(&self, g: usize) -> bool {
+            assert!(g < self.size());
+            self.unit_is_ok(Unit::Group(g))
+        }
+
+        // The main diagonal for 0, the anti-diagonal for 1. Checked whether
+        // diagonals count or not.
+        pub fn diagonal_is_ok(&self, k: usize) -> bool {
+            assert!(k < 2);
+            self.unit_is_ok(Unit::Diagonal(k))
+        }
+
+        fn unit_is_ok(&self, unit: Unit) -> bool {
+            let mut seen: u32 = 0;
+            for (r, c) in self.cells_of(unit) {
+                if let Cell::Digit(d) = self.grid[r][c] {
+                    if (seen & bit(d)) != 0 {
+                        return false;
                     }
+                    seen |= bit(d);
                 }
             }
             true
         }
 
+        pub fn group_of(&self, r: usize, c: usize) -> usize {
+            group_of(self.shape, self.regions(), r, c)
+        }
+
+        // Rows, then columns, then groups, then the diagonals when they count.
+        pub fn units(&self) -> Vec<Unit> {
+            units(self.size(), self.diagonals)
+        }
+
+        pub fn cells_of(&self, unit: Unit) -> Vec<(usize, usize)> {
+            cells_of(self.shape, self.regions(), unit)
+        }
+
+        // Every digit given more than once in a unit, with all its cells.
+        pub fn conflicts(&self) -> Vec<Conflict> {
+            let mut res = vec![];
+            for unit in self.units() {
+                for d in 1..=self.size() as u8 {
+                    let cells: Vec<(usize, usize)> = self
+                        .cells_of(unit)
+                        .into_iter()
+                        .filter(|&(r, c)| self.grid[r][c] == Cell::Digit(d))
+                        .collect();
+                    if cells.len() > 1 {
+                        res.push(Conflict {
+                            unit,
+                            digit: d,
+                            cells,
+                        });
+                    }
+                }
+            }
+            res
+        }
+
         pub fn solved(&self) -> bool {
-            for i in 0..9 {
+            for i in 0..self.size() {
                 if !self.row_is_ok(i) || !self.col_is_ok(i) {
                     return false;
                 }
             }
-            for gr in 0..3 {
-                for gc in 0..3 {
+            for gr in 0..self.shape.box_cols {
+                for gc in 0..self.shape.box_rows {
                     if !self.group_is_ok(gr, gc) {
                         return false;
                     }
                 }
             }
+            if self.diagonals && !(self.diagonal_is_ok(0) && self.diagonal_is_ok(1)) {
+                return false;
+            }
             self.empty_cells().is_empty()
         }
 
-        pub fn read() -> Result<Table, &'static str> {
-            let mut grid: [[u8; 9]; 9] = Default::default();
-            for r in 0..9 {
+        // One row per line, digits separated by whitespace and `0` for empty.
+        // The first line sets the size of the grid.
+        pub fn read() -> Result<Table, Error> {
+            Table::read_rows(None).map(Table::from_rows)
+        }
+
+        // Like `read`, with `shape` setting the size and boxes.
+        pub fn read_with_shape(shape: Shape) -> Result<Table, Error> {
+            let rows = Table::read_rows(Some(shape.size()))?;
+            Ok(Table::from_rows_with_shape(rows, shape))
+        }
+
+        // The first line sets the size unless it is given.
+        fn read_rows(mut size: Option<usize>) -> Result<Vec<Vec<u8>>, Error> {
+            let mut rows: Vec<Vec<u8>> = vec![];
+            while rows.len() < size.unwrap_or(1) {
+                let r = rows.len();
                 let mut buf = String::new();
-                if let Err(_e) = io::stdin().read_line(&mut buf) {
-                    return Err("Couldn't read from stdin.");
+                match io::stdin().read_line(&mut buf) {
+                    Ok(0) => return Err(Error::Io(io::ErrorKind::UnexpectedEof)),
+                    Ok(_) => {}
+                    Err(e) => return Err(Error::Io(e.kind())),
                 }
-                let digits: Vec<u32> = buf
-                    .split_whitespace()
-                    .filter_map(|s| s.parse().ok())
-                    .collect();
+                let size =
+                    *size.get_or_insert_with(|| Shape::nearest(tokens(&buf).count().pow(2)).size());
+                let mut row = Vec::with_capacity(size);
+                let mut end = 0;
+                for (column, token) in tokens(&buf) {
+                    let d = match token.parse::<u8>() {
+                        Ok(d) if d as usize <= size && row.len() < size => d,
+                        _ => return Err(Error::parse(r + 1, column, token)),
+                    };
+                    row.push(d);
+                    end = column + token.chars().count();
+                }
+                if row.len() != size {
+                    return Err(Error::parse(r + 1, end + 1, ""));
+                }
+                rows.push(row);
+            }
+            Ok(rows)
+        }
+
+        // One puzzle per line: all cells in row-major order, `0` or `.` for
+        // empty and letters from `A` for digits above 9. The number of cells
+        // sets the size of the grid, boxes get the default shape for it.
+        pub fn from_line(line: &str) -> Result<Table, Error> {
+            let count = line.chars().filter(|ch| !ch.is_whitespace()).count();
+            Table::from_line_with_shape(line, Shape::nearest(count))
+        }
 
-                if digits.len() != 9 {
-                    return Err("Please follow input format.");
+        // Like `from_line`, for a grid of `shape` whatever the cell count.
+        pub fn from_line_with_shape(line: &str, shape: Shape) -> Result<Table, Error> {
+            let size = shape.size();
+            let mut rows = vec![vec![0; size]; size];
+            let mut cells = 0;
+            for (i, ch) in line.chars().enumerate() {
+                if ch.is_whitespace() {
+                    continue;
                 }
-                for c in 0..9 {
-                    grid[r][c] = digits[c] as u8;
+                match symbol_value(ch) {
+                    Some(d) if cells < size * size && d as usize <= size => {
+                        rows[cells / size][cells % size] = d;
+                    }
+                    _ => return Err(Error::parse(1, i + 1, &ch.to_string())),
                 }
+                cells += 1;
+            }
+            if cells != size * size {
+                return Err(Error::parse(1, line.chars().count() + 1, ""));
             }
-            Ok(Table::from_arr(grid))
+            Ok(Table::from_rows_with_shape(rows, shape))
         }
 
-        pub fn print(&self) {
-            for r in 0..9 {
-                for c in 0..9 {
-                    let v;
-                    if let Cell::Digit(d) = self.grid[r][c] {
-                        v = d;
-                    } else {
-                        v = 0;
+        // Like `from_line`, for a jigsaw grid with `regions` for groups.
+        // Region maps of a size no grid has are Unsupported.
+        pub fn from_line_with_regions(line: &str, regions: &Regions) -> Result<Table, Error> {
+            let size = regions.size();
+            let shape = Shape::for_size(size).ok_or_else(|| Error::Unsupported {
+                variant: format!("{}x{} jigsaw", size, size),
+            })?;
+            let mut res = Table::from_line_with_shape(line, shape)?;
+            res.set_regions(Some(regions.clone()));
+            Ok(res)
+        }
+
+        pub fn to_line(&self) -> String {
+            let mut res = String::with_capacity(self.size() * self.size());
+            for row in self.grid.iter() {
+                for cell in row.iter() {
+                    match cell {
+                        Cell::Digit(d) => res.push(symbol(*d)),
+                        Cell::Empty => res.push('.'),
                     }
-                    if c != 8 {
-                        print!(" {} |", v);
-                    } else {
-                        println!(" {} ", v);
+                }
+            }
+            res
+        }
+
+        // Rows of digits, `0` for empty, with lines between bands and
+        // stacks. Jigsaw regions don't line up, so they get no lines.
+        pub fn print(&self) {
+            let size = self.size();
+            let width = size.to_string().len();
+            let boxed = self.regions.is_none();
+            for r in 0..size {
+                let mut line = String::new();
+                for c in 0..size {
+                    if boxed && c != 0 && c.is_multiple_of(self.shape.box_cols) {
+                        line.push_str(" |");
                     }
+                    let v = match self.grid[r][c] {
+                        Cell::Digit(d) => d,
+                        Cell::Empty => 0,
+                    };
+                    line.push_str(&format!(" {:>width$}", v));
                 }
-                if r != 9 {
-                    println!("-----------------------------------");
+                println!("{}", line);
+                if boxed && r + 1 != size && (r + 1).is_multiple_of(self.shape.box_rows) {
+                    let border: String = line
+                        .chars()
+                        .map(|ch| if ch == '|' { '+' } else { '-' })
+                        .collect();
+                    println!("{}", border);
                 }
             }
         }
     }
 
-    impl Default for Cell {
-        fn default() -> Self {
-            Cell::Empty
+    // Groups are numbered row by row, like cells inside a group. Diagonal 0
+    // runs from the top left corner, diagonal 1 from the top right one.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    pub enum Unit {
+        Row(usize),
+        Col(usize),
+        Group(usize),
+        Diagonal(usize),
+    }
+
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct Conflict {
+        pub unit: Unit,
+        pub digit: u8,
+        pub cells: Vec<(usize, usize)>,
+    }
+
+    impl fmt::Display for Unit {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Unit::Row(i) => write!(f, "row {}", i + 1),
+                Unit::Col(i) => write!(f, "column {}", i + 1),
+                Unit::Group(i) => write!(f, "group {}", i + 1),
+                Unit::Diagonal(0) => write!(f, "main diagonal"),
+                Unit::Diagonal(_) => write!(f, "anti-diagonal"),
+            }
+        }
+    }
+
+    impl fmt::Display for Conflict {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{} repeated in {} at", self.digit, self.unit)?;
+            for (i, (r, c)) in self.cells.iter().enumerate() {
+                let sep = if i == 0 { " " } else { ", " };
+                write!(f, "{}r{}c{}", sep, r + 1, c + 1)?;
+            }
+            Ok(())
+        }
+    }
+
+    // Whitespace separated tokens with their 1-based column.
+    pub(crate) fn tokens(line: &str) -> impl Iterator<Item = (usize, &str)> {
+        let mut start = None;
+        let mut res = vec![];
+        for (i, (pos, ch)) in line.char_indices().enumerate() {
+            match (ch.is_whitespace(), start) {
+                (false, None) => start = Some((i, pos)),
+                (true, Some((column, from))) => {
+                    res.push((column + 1, &line[from..pos]));
+                    start = None;
+                }
+                _ => {}
+            }
         }
+        if let Some((column, from)) = start {
+            res.push((column + 1, &line[from..]));
+        }
+        res.into_iter()
     }
 
     impl Default for Table {
         fn default() -> Self {
-            Table {
-                grid: [[Cell::Empty; 9]; 9],
-            }
+            Table::with_shape(Shape::default())
         }
     }
 
-    impl Clone for Table {
-        fn clone(&self) -> Self {
-            Table {
-                grid: self.grid.clone(),
+    // Layout shared by tables and candidates: regions replace the boxes.
+    fn group_of(shape: Shape, regions: Option<&Regions>, r: usize, c: usize) -> usize {
+        match regions {
+            Some(regions) => regions.region_of(r, c),
+            None => shape.group_of(r, c),
+        }
+    }
+
+    fn units(size: usize, diagonals: bool) -> Vec<Unit> {
+        let diagonals = if diagonals { 0..2 } else { 0..0 };
+        (0..size)
+            .map(Unit::Row)
+            .chain((0..size).map(Unit::Col))
+            .chain((0..size).map(Unit::Group))
+            .chain(diagonals.map(Unit::Diagonal))
+            .collect()
+    }
+
+    fn cells_of(shape: Shape, regions: Option<&Regions>, unit: Unit) -> Vec<(usize, usize)> {
+        let size = shape.size();
+        match unit {
+            Unit::Row(r) => (0..size).map(|c| (r, c)).collect(),
+            Unit::Col(c) => (0..size).map(|r| (r, c)).collect(),
+            Unit::Group(g) => match regions {
+                Some(regions) => regions.cells(g).to_vec(),
+                None => shape.group_cells(g).collect(),
+            },
+            Unit::Diagonal(0) => (0..size).map(|i| (i, i)).collect(),
+            Unit::Diagonal(_) => (0..size).map(|i| (i, size - 1 - i)).collect(),
+        }
+    }
+
+    pub fn bit(d: u8) -> u32 {
+        1 << (d - 1)
+    }
+
+    pub fn mask_digits(mut mask: u32) -> impl Iterator<Item = u8> {
+        std::iter::from_fn(move || {
+            if mask == 0 {
+                return None;
+            }
+            let d = mask.trailing_zeros() as u8 + 1;
+            mask &= mask - 1;
+            Some(d)
+        })
+    }
+
+    // Legal digits of every empty cell as a mask (bit `d - 1` for digit
+    // `d`), next to the digits already used by every row, column and group,
+    // and by the diagonals when they count. Filled cells have an empty mask.
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    pub struct Candidates {
+        shape: Shape,
+        regions: Option<Arc<Regions>>,
+        values: Vec<u8>,
+        cells: Vec<u32>,
+        rows: Vec<u32>,
+        cols: Vec<u32>,
+        groups: Vec<u32>,
+        diagonals: Option<[u32; 2]>,
+    }
+
+    impl Candidates {
+        pub fn new(t: &Table) -> Result<Candidates, Error> {
+            let size = t.size();
+            let mut res = Candidates {
+                shape: t.shape(),
+                regions: t.regions.clone(),
+                values: vec![0; size * size],
+                cells: vec![(1 << size) - 1; size * size],
+                rows: vec![0; size],
+                cols: vec![0; size],
+                groups: vec![0; size],
+                diagonals: t.diagonals.then_some([0; 2]),
+            };
+            for r in 0..size {
+                for c in 0..size {
+                    if let Cell::Digit(d) = t.grid[r][c] {
+                        if !res.can_place(r, c, d) {
+                            let conflicts = t.conflicts();
+                            return Err(Error::Contradiction { conflicts });
+                        }
+                        res.place(r, c, d);
+                    }
+                }
+            }
+            Ok(res)
+        }
+
+        pub fn size(&self) -> usize {
+            self.shape.size()
+        }
+
+        pub fn shape(&self) -> Shape {
+            self.shape
+        }
+
+        pub fn value(&self, r: usize, c: usize) -> Option<u8> {
+            match self.values[r * self.size() + c] {
+                0 => None,
+                d => Some(d),
             }
         }
+
+        pub fn mask(&self, r: usize, c: usize) -> u32 {
+            self.cells[r * self.size() + c]
+        }
+
+        pub fn digits(&self, r: usize, c: usize) -> Vec<u8> {
+            mask_digits(self.mask(r, c)).collect()
+        }
+
+        pub fn count(&self, r: usize, c: usize) -> u32 {
+            self.mask(r, c).count_ones()
+        }
+
+        pub fn can_place(&self, r: usize, c: usize, d: u8) -> bool {
+            self.mask(r, c) & bit(d) != 0
+        }
+
+        pub fn place(&mut self, r: usize, c: usize, d: u8) {
+            let size = self.size();
+            assert!(self.values[r * size + c] == 0);
+            let b = bit(d);
+            self.values[r * size + c] = d;
+            self.cells[r * size + c] = 0;
+            self.rows[r] |= b;
+            self.cols[c] |= b;
+            let g = self.group_of(r, c);
+            self.groups[g] |= b;
+            for k in self.diagonals_through(r, c) {
+                if let Some(diagonals) = self.diagonals.as_mut() {
+                    diagonals[k] |= b;
+                }
+            }
+            for (pr, pc) in self.peers(r, c) {
+                self.cells[pr * size + pc] &= !b;
+            }
+        }
+
+        // Candidates coming back to the peers are derived from the unit
+        // occupancy, so eliminations made on `d` around the cell are lost.
+        pub fn remove(&mut self, r: usize, c: usize) {
+            let size = self.size();
+            let d = self.values[r * size + c];
+            assert!(d != 0);
+            let b = bit(d);
+            self.values[r * size + c] = 0;
+            self.rows[r] &= !b;
+            self.cols[c] &= !b;
+            let g = self.group_of(r, c);
+            self.groups[g] &= !b;
+            for k in self.diagonals_through(r, c) {
+                if let Some(diagonals) = self.diagonals.as_mut() {
+                    diagonals[k] &= !b;
+                }
+            }
+            self.cells[r * size + c] = self.legal(r, c);
+            for (pr, pc) in self.peers(r, c) {
+                if self.values[pr * size + pc] == 0 {
+                    self.cells[pr * size + pc] |= self.legal(pr, pc) & b;
+                }
+            }
+        }
+
+        // Removes `d` from the candidates of a cell, returns whether it was there.
+        pub fn eliminate(&mut self, r: usize, c: usize, d: u8) -> bool {
+            let had = self.can_place(r, c, d);
+            let size = self.size();
+            self.cells[r * size + c] &= !bit(d);
+            had
+        }
+
+        pub fn regions(&self) -> Option<&Regions> {
+            self.regions.as_deref()
+        }
+
+        pub fn diagonals(&self) -> bool {
+            self.diagonals.is_some()
+        }
+
+        // Diagonals that count and pass through (r, c).
+        fn diagonals_through(&self, r: usize, c: usize) -> impl Iterator<Item = usize> {
+            let size = self.size();
+            let on = self.diagonals.is_some();
+            [on && r == c, on && r + c + 1 == size]
+                .into_iter()
+                .enumerate()
+                .filter_map(|(k, through)| through.then_some(k))
+        }
+
+        pub fn group_of(&self, r: usize, c: usize) -> usize {
+            group_of(self.shape, self.regions(), r, c)
+        }
+
+        // Rows, then columns, then groups, then the diagonals when they count.
+        pub fn units(&self) -> Vec<Unit> {
+            units(self.size(), self.diagonals())
+        }
+
+        pub fn cells_of(&self, unit: Unit) -> Vec<(usize, usize)> {
+            cells_of(self.shape, self.regions(), unit)
+        }
+
+        // Cells sharing a row, column, group or counting diagonal with (r, c),
+        // some more than once and (r, c) itself among them.
+        fn peers(&self, r: usize, c: usize) -> impl Iterator<Item = (usize, usize)> {
+            let size = self.size();
+            let g = self.group_of(r, c);
+            // Owned, so that the cells can change while the peers are walked.
+            let regions = self.regions.clone();
+            let group =
+                self.shape
+                    .group_cells(g)
+                    .enumerate()
+                    .map(move |(i, cell)| match &regions {
+                        Some(regions) => regions.cells(g)[i],
+                        None => cell,
+                    });
+            let main = self.diagonals.is_some() && r == c;
+            let anti = self.diagonals.is_some() && r + c + 1 == size;
+            (0..size)
+                .flat_map(move |i| {
+                    [
+                        (r, i),
+                        (i, c),
+                        if main { (i, i) } else { (r, c) },
+                        if anti { (i, size - 1 - i) } else { (r, c) },
+                    ]
+                })
+                .chain(group)
+        }
+
+        fn legal(&self, r: usize, c: usize) -> u32 {
+            let all = (1 << self.size()) - 1;
+            let mut used = self.rows[r] | self.cols[c] | self.groups[self.group_of(r, c)];
+            for k in self.diagonals_through(r, c) {
+                used |= self.diagonals.map_or(0, |diagonals| diagonals[k]);
+            }
+            all & !used
+        }
+
+        pub fn to_table(&self) -> Table {
+            let mut t = Table::with_shape(self.shape);
+            t.regions = self.regions.clone();
+            t.diagonals = self.diagonals.is_some();
+            for r in 0..self.size() {
+                for c in 0..self.size() {
+                    if let Some(d) = self.value(r, c) {
+                        t.grid[r][c] = Cell::Digit(d);
+                    }
+                }
+            }
+            t
+        }
     }
 }
 
 pub mod solver {
-    use crate::grid::{Cell, Table};
+    pub mod dlx;
+    pub mod logic;
+
+    pub use dlx::solve_dlx;
+
+    use crate::error::Error;
+    use crate::grid::{Candidates, Cell, Table};
+    use dlx::Dlx;
+    use rand::rngs::StdRng;
     use rand::seq::SliceRandom;
-    use rand::{thread_rng, Rng};
+    use rand::{thread_rng, Rng, SeedableRng};
+    use std::sync::atomic::{AtomicBool, Ordering};
 
     pub const ANNEALING_ITERS: u32 = 130000;
 
-    fn dfs(t: &mut Table, i: usize, emptys: &Vec<(usize, usize)>) -> bool {
+    fn dfs(cand: &mut Candidates, i: usize, emptys: &[(usize, usize)]) -> bool {
         if emptys.len() <= i {
             return true;
         }
-        for d in 1..=9 {
-            let (row, col) = emptys[i];
-            t.grid[row][col] = Cell::Digit(d);
-            if t.row_is_ok(row)
-                && t.col_is_ok(col)
-                && t.group_is_ok(row / 3, col / 3)
-                && dfs(t, i + 1, emptys)
-            {
-                return true;
+        let (row, col) = emptys[i];
+        for d in 1..=cand.size() as u8 {
+            if cand.can_place(row, col, d) {
+                cand.place(row, col, d);
+                if dfs(cand, i + 1, emptys) {
+                    return true;
+                }
+                cand.remove(row, col);
             }
         }
-        t.grid[emptys[i].0][emptys[i].1] = Cell::Empty;
         false
     }
 
-    fn dfs_rand(t: &mut Table, i: usize, emptys: &Vec<(usize, usize)>) -> bool {
+    // Same search as `dfs`, but digits are tried in random order. Every
+    // placement tried is counted in `nodes`; raising `cancel` unwinds it.
+    fn dfs_rand<R: Rng>(
+        cand: &mut Candidates,
+        i: usize,
+        emptys: &[(usize, usize)],
+        rng: &mut R,
+        nodes: &mut u64,
+        cancel: &AtomicBool,
+    ) -> bool {
         if emptys.len() <= i {
             return true;
         }
-        let mut order: Vec<u8> = (1..=9).collect();
-        order.shuffle(&mut thread_rng());
+        let (row, col) = emptys[i];
+        let mut order = cand.digits(row, col);
+        order.shuffle(rng);
         for d in order {
-            let (row, col) = emptys[i];
-            t.grid[row][col] = Cell::Digit(d);
-            if t.row_is_ok(row)
-                && t.col_is_ok(col)
-                && t.group_is_ok(row / 3, col / 3)
-                && dfs(t, i + 1, emptys)
-            {
+            if cancel.load(Ordering::Relaxed) {
+                break;
+            }
+            *nodes += 1;
+            cand.place(row, col, d);
+            if dfs_rand(cand, i + 1, emptys, rng, nodes, cancel) {
                 return true;
             }
+            cand.remove(row, col);
         }
-        t.grid[emptys[i].0][emptys[i].1] = Cell::Empty;
         false
     }
 
-    pub fn solve_dfs_single(mut t: Table) -> Result<Table, &'static str> {
+    pub fn solve_dfs_single(t: Table) -> Result<Table, Error> {
+        let mut cand = Candidates::new(&t)?;
         let emptys = t.empty_cells();
-        if dfs(&mut t, 0, &emptys) {
-            Ok(t)
+        if dfs(&mut cand, 0, &emptys) {
+            Ok(cand.to_table())
         } else {
-            Err("No solution found")
+            Err(Error::NoSolution)
+        }
+    }
+
+    // Fills naked and hidden singles until none are left. Returns false on
+    // a contradiction: a cell without candidates or a digit without a place.
+    fn propagate(cand: &mut Candidates, units: &[Vec<(usize, usize)>]) -> bool {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for r in 0..cand.size() {
+                for c in 0..cand.size() {
+                    if cand.value(r, c).is_some() {
+                        continue;
+                    }
+                    match cand.digits(r, c)[..] {
+                        [] => return false,
+                        [d] => {
+                            cand.place(r, c, d);
+                            changed = true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            for unit in units {
+                for d in 1..=cand.size() as u8 {
+                    let mut spot = None;
+                    let mut spots = 0;
+                    for &(r, c) in unit {
+                        if cand.value(r, c) == Some(d) {
+                            spots = usize::MAX;
+                            break;
+                        }
+                        if cand.can_place(r, c, d) {
+                            spot = Some((r, c));
+                            spots += 1;
+                        }
+                    }
+                    match (spots, spot) {
+                        (0, _) => return false,
+                        (1, Some((r, c))) => {
+                            cand.place(r, c, d);
+                            changed = true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
         }
+        true
     }
 
-    pub fn solve_randomized_dfs_single(mut t: Table) -> Result<Table, &'static str> {
+    fn propagating_dfs(cand: Candidates, units: &[Vec<(usize, usize)>]) -> Option<Candidates> {
+        let mut best = None;
+        for r in 0..cand.size() {
+            for c in 0..cand.size() {
+                if cand.value(r, c).is_none()
+                    && best.is_none_or(|(br, bc)| cand.count(r, c) < cand.count(br, bc))
+                {
+                    best = Some((r, c));
+                }
+            }
+        }
+        let (row, col) = match best {
+            Some(cell) => cell,
+            None => return Some(cand),
+        };
+        // Every branch works on its own copy, so backtracking just drops it.
+        for d in cand.digits(row, col) {
+            let mut next = cand.clone();
+            next.place(row, col, d);
+            if propagate(&mut next, units) {
+                if let Some(res) = propagating_dfs(next, units) {
+                    return Some(res);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn solve_propagating_dfs(t: Table) -> Result<Table, Error> {
+        let mut cand = Candidates::new(&t)?;
+        let units: Vec<_> = t.units().into_iter().map(|u| t.cells_of(u)).collect();
+        if !propagate(&mut cand, &units) {
+            return Err(Error::NoSolution);
+        }
+        match propagating_dfs(cand, &units) {
+            Some(res) => Ok(res.to_table()),
+            None => Err(Error::NoSolution),
+        }
+    }
+
+    // Walks the same search tree as `dfs`, but keeps the placed digits on an
+    // explicit stack so that the search can pause after every completion.
+    struct Solutions {
+        cand: Option<Candidates>,
+        emptys: Vec<(usize, usize)>,
+        stack: Vec<u8>,
+        resume: u8,
+    }
+
+    impl Iterator for Solutions {
+        type Item = Table;
+
+        fn next(&mut self) -> Option<Table> {
+            let cand = self.cand.as_mut()?;
+            loop {
+                let i = self.stack.len();
+                if i == self.emptys.len() {
+                    let res = cand.to_table();
+                    match self.stack.pop() {
+                        Some(d) => {
+                            let (row, col) = self.emptys[i - 1];
+                            cand.remove(row, col);
+                            self.resume = d + 1;
+                        }
+                        None => self.cand = None,
+                    }
+                    return Some(res);
+                }
+                let (row, col) = self.emptys[i];
+                match (self.resume..=cand.size() as u8).find(|&d| cand.can_place(row, col, d)) {
+                    Some(d) => {
+                        cand.place(row, col, d);
+                        self.stack.push(d);
+                        self.resume = 1;
+                    }
+                    None => match self.stack.pop() {
+                        Some(d) => {
+                            let (row, col) = self.emptys[i - 1];
+                            cand.remove(row, col);
+                            self.resume = d + 1;
+                        }
+                        None => {
+                            self.cand = None;
+                            return None;
+                        }
+                    },
+                }
+            }
+        }
+    }
+
+    // Yields every completion of `t` on demand, in the order `dfs` finds them.
+    pub fn solutions(t: Table) -> impl Iterator<Item = Table> {
+        Solutions {
+            cand: Candidates::new(&t).ok(),
+            emptys: t.empty_cells(),
+            stack: vec![],
+            resume: 1,
+        }
+    }
+
+    // Counts the completions of `t`, stopping once `limit` of them are found.
+    // Contradictory givens have no completion.
+    pub fn count_solutions(t: &Table, limit: usize) -> usize {
+        let mut dlx = match Dlx::new(t) {
+            Ok(dlx) => dlx,
+            Err(_e) => return 0,
+        };
+        let mut res = 0;
+        if limit > 0 {
+            dlx.search(&mut |_solution: &Table| {
+                res += 1;
+                res < limit
+            });
+        }
+        res
+    }
+
+    pub fn is_unique(t: &Table) -> bool {
+        count_solutions(t, 2) == 1
+    }
+
+    // A unique puzzle that loses uniqueness without any one of its givens.
+    pub fn is_minimal(t: &Table) -> bool {
+        if !is_unique(t) {
+            return false;
+        }
+        let mut t = t.clone();
+        for r in 0..t.size() {
+            for c in 0..t.size() {
+                let given = t.grid[r][c];
+                if given == Cell::Empty {
+                    continue;
+                }
+                t.grid[r][c] = Cell::Empty;
+                let unique = is_unique(&t);
+                t.grid[r][c] = given;
+                if unique {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    // A complete grid drawn from `rng`.
+    pub(crate) fn random_grid<R: Rng>(rng: &mut R) -> Table {
+        let t = Table::default();
+        let mut cand = Candidates::new(&t).expect("an empty grid is consistent");
+        let emptys = t.empty_cells();
+        let mut nodes = 0;
+        dfs_rand(
+            &mut cand,
+            0,
+            &emptys,
+            rng,
+            &mut nodes,
+            &AtomicBool::new(false),
+        );
+        cand.to_table()
+    }
+
+    pub fn solve_randomized_dfs_single(t: Table) -> Result<Table, Error> {
+        solve_randomized_dfs_seeded(t, thread_rng().gen(), &AtomicBool::new(false)).0
+    }
+
+    // Cell and digit order are both drawn from `seed`, so a run can be
+    // replayed. The number of search nodes is returned whatever the outcome.
+    pub fn solve_randomized_dfs_seeded(
+        t: Table,
+        seed: u64,
+        cancel: &AtomicBool,
+    ) -> (Result<Table, Error>, u64) {
+        let mut cand = match Candidates::new(&t) {
+            Ok(cand) => cand,
+            Err(e) => return (Err(e), 0),
+        };
+        let mut rng = StdRng::seed_from_u64(seed);
         let mut emptys = t.empty_cells();
-        emptys.shuffle(&mut thread_rng());
-        if dfs_rand(&mut t, 0, &emptys) {
-            Ok(t)
+        emptys.shuffle(&mut rng);
+        let mut nodes = 0;
+        if dfs_rand(&mut cand, 0, &emptys, &mut rng, &mut nodes, cancel) {
+            (Ok(cand.to_table()), nodes)
+        } else if cancel.load(Ordering::Relaxed) {
+            (Err(Error::Cancelled), nodes)
         } else {
-            Err("No solution found")
+            (Err(Error::NoSolution), nodes)
         }
     }
 
-    fn generate_digits(sz: usize) -> Vec<u8> {
-        let mut res = vec![];
-        res.reserve(sz);
+    fn generate_digits(sz: usize, size: usize) -> Vec<u8> {
+        let mut res = Vec::with_capacity(sz);
         for _i in 0..sz {
-            res.push(thread_rng().gen_range(1..=9));
+            res.push(thread_rng().gen_range(1..=size as u8));
         }
         res
     }
 
-    fn apply(t: &mut Table, emptys: &Vec<(usize, usize)>, values: &Vec<u8>) {
+    fn apply(t: &mut Table, emptys: &[(usize, usize)], values: &[u8]) {
         assert_eq!(emptys.len(), values.len());
         for i in 0..emptys.len() {
             t.grid[emptys[i].0][emptys[i].1] = Cell::Digit(values[i]);
         }
     }
 
-    fn inversions(t: &Table) -> i16 {
+    // Number of repeated digits in a unit, zero for a valid one.
+    fn duplicates(cells: impl Iterator<Item = Cell>) -> i16 {
         let mut res = 0;
-        for i in 0..9 {
-            if !t.row_is_ok(i) {
-                res += 1;
-            }
-            if !t.col_is_ok(i) {
-                res += 1;
-            }
-        }
-        for gr in 0..3 {
-            for gc in 0..3 {
-                if !t.group_is_ok(gr, gc) {
+        let mut seen: u32 = 0;
+        for cell in cells {
+            if let Cell::Digit(d) = cell {
+                if (seen & (1 << d)) != 0 {
                     res += 1;
                 }
+                seen |= 1 << d;
             }
         }
         res
     }
 
-    fn calc_change(t: &mut Table, r: usize, c: usize, d: u8) -> i16 {
-        let old = t.grid[r][c];
+    fn row_inversions(t: &Table, r: usize) -> i16 {
+        duplicates(t.grid[r].iter().copied())
+    }
 
-        let mut had = 0;
-        if !t.row_is_ok(r) {
-            had += 1;
+    fn col_inversions(t: &Table, c: usize) -> i16 {
+        duplicates(t.grid.iter().map(|row| row[c]))
+    }
+
+    fn group_inversions(t: &Table, g: usize) -> i16 {
+        match t.regions() {
+            Some(regions) => duplicates(regions.cells(g).iter().map(|&(r, c)| t.grid[r][c])),
+            None => duplicates(t.shape().group_cells(g).map(|(r, c)| t.grid[r][c])),
         }
-        if !t.col_is_ok(c) {
-            had += 1;
+    }
+
+    // Summed over the diagonals through (r, c), if they count.
+    fn diagonal_inversions(t: &Table, r: usize, c: usize) -> i16 {
+        let size = t.size();
+        let mut res = 0;
+        if t.diagonals() && r == c {
+            res += duplicates((0..size).map(|i| t.grid[i][i]));
         }
-        if !t.group_is_ok(r / 3, c / 3) {
-            had += 1;
+        if t.diagonals() && r + c + 1 == size {
+            res += duplicates((0..size).map(|i| t.grid[i][size - 1 - i]));
         }
+        res
+    }
 
-        t.grid[r][c] = Cell::Digit(d);
-        let mut got = 0;
-        if !t.row_is_ok(r) {
-            got += 1;
-        }
-        if !t.col_is_ok(c) {
-            got += 1;
+    fn inversions(t: &Table) -> i16 {
+        let mut res = 0;
+        for i in 0..t.size() {
+            res += row_inversions(t, i) + col_inversions(t, i);
         }
-        if !t.group_is_ok(r / 3, c / 3) {
-            got += 1;
+        for g in 0..t.size() {
+            res += group_inversions(t, g);
         }
+        // The corners of the top row lie on one diagonal each.
+        res += diagonal_inversions(t, 0, 0) + diagonal_inversions(t, 0, t.size() - 1);
+        res
+    }
 
+    fn calc_change(t: &mut Table, r: usize, c: usize, d: u8) -> i16 {
+        let old = t.grid[r][c];
+        let g = t.group_of(r, c);
+        let units = |t: &Table| {
+            row_inversions(t, r)
+                + col_inversions(t, c)
+                + group_inversions(t, g)
+                + diagonal_inversions(t, r, c)
+        };
+        let had = units(t);
+        t.grid[r][c] = Cell::Digit(d);
+        let got = units(t);
         t.grid[r][c] = old;
         got - had
     }
@@ -321,19 +1441,34 @@ pub mod solver {
         }
     }
 
-    pub fn solve_simulated_annealing_single(mut t: Table) -> Result<Table, &'static str> {
+    pub fn solve_simulated_annealing_single(t: Table) -> Result<Table, Error> {
+        solve_simulated_annealing_chain(t, &AtomicBool::new(false)).0
+    }
+
+    // Runs one annealing chain, giving up as soon as `cancel` is raised.
+    // The number of iterations spent is returned whatever the outcome.
+    pub fn solve_simulated_annealing_chain(
+        mut t: Table,
+        cancel: &AtomicBool,
+    ) -> (Result<Table, Error>, u32) {
+        if let Err(e) = Candidates::new(&t) {
+            return (Err(e), 0);
+        }
         let emptys = t.empty_cells();
 
-        let mut values = generate_digits(emptys.len());
+        let mut values = generate_digits(emptys.len(), t.size());
         apply(&mut t, &emptys, &values);
         let mut opt: i16 = inversions(&t);
 
         let mut temperature: f32 = 1.0;
         let mut iters = 0;
 
-        while opt != 0 && iters < ANNEALING_ITERS {
+        while opt != 0 && !emptys.is_empty() && iters < ANNEALING_ITERS {
+            if cancel.load(Ordering::Relaxed) {
+                return (Err(Error::Cancelled), iters);
+            }
             let i = thread_rng().gen_range(0..emptys.len());
-            let d = thread_rng().gen_range(1..=9);
+            let d = thread_rng().gen_range(1..=t.size() as u8);
 
             let delta = calc_change(&mut t, emptys[i].0, emptys[i].1, d);
 
@@ -343,19 +1478,117 @@ pub mod solver {
                 opt += delta;
             }
 
-            temperature *= 0.99;
+            temperature *= 0.99995;
             iters += 1;
         }
         if t.solved() {
-            Ok(t)
+            (Ok(t), iters)
         } else {
-            Err("No solution found")
+            let iterations = iters as u64;
+            (Err(Error::BudgetExhausted { iterations }), iters)
         }
     }
 }
 
+pub mod generator;
+
+pub mod rating;
+
+pub mod transform;
+
 pub mod app {
-    use crate::grid::Table;
+    use crate::error::Error;
+    use crate::grid::{Regions, Shape, Table};
+    use std::fs;
+    use std::io::{self, Read};
+    use std::process;
+
+    // Reads the whole file, or stdin when no path (or "-") is given.
+    pub fn read_input(path: Option<&str>) -> io::Result<String> {
+        match path {
+            Some(p) if p != "-" => fs::read_to_string(p),
+            _ => {
+                let mut buf = String::new();
+                io::stdin().read_to_string(&mut buf)?;
+                Ok(buf)
+            }
+        }
+    }
+
+    // Parses one puzzle per line, skipping blank lines and `#` comments.
+    // Every puzzle is paired with its 1-based line number.
+    pub fn puzzles_from_lines(input: &str) -> Vec<(usize, Result<Table, Error>)> {
+        parse_lines(input, Table::from_line)
+    }
+
+    // Like `puzzles_from_lines`, every puzzle having boxes of `shape`.
+    pub fn puzzles_from_lines_with_shape(
+        input: &str,
+        shape: Shape,
+    ) -> Vec<(usize, Result<Table, Error>)> {
+        parse_lines(input, |line| Table::from_line_with_shape(line, shape))
+    }
+
+    // Like `puzzles_from_lines`, every puzzle having `regions` for groups.
+    pub fn puzzles_from_lines_with_regions(
+        input: &str,
+        regions: &Regions,
+    ) -> Vec<(usize, Result<Table, Error>)> {
+        parse_lines(input, |line| Table::from_line_with_regions(line, regions))
+    }
+
+    fn parse_lines(
+        input: &str,
+        parse: impl Fn(&str) -> Result<Table, Error>,
+    ) -> Vec<(usize, Result<Table, Error>)> {
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| {
+                let line = line.trim();
+                !line.is_empty() && !line.starts_with('#')
+            })
+            .map(|(i, line)| {
+                let puzzle = parse(line).map_err(|e| match e {
+                    Error::Parse { column, token, .. } => Error::Parse {
+                        line: i + 1,
+                        column,
+                        token,
+                    },
+                    e => e,
+                });
+                (i + 1, puzzle)
+            })
+            .collect()
+    }
+
+    // Box shape given as `ROWSxCOLS` on the command line, like `2x3`.
+    pub fn parse_shape(v: &str) -> Option<Shape> {
+        let (rows, cols) = v.split_once('x')?;
+        let (rows, cols): (usize, usize) = (rows.parse().ok()?, cols.parse().ok()?);
+        let sides = 2..=5;
+        (sides.contains(&rows) && sides.contains(&cols)).then(|| Shape::new(rows, cols))
+    }
+
+    // Region map for jigsaw puzzles from the file at `path`, in the
+    // format of `Regions::from_text`.
+    pub fn read_regions(path: &str) -> Result<Regions, String> {
+        let text =
+            fs::read_to_string(path).map_err(|e| format!("Couldn't read {}: {}.", path, e))?;
+        Regions::from_text(&text).map_err(|e| format!("Invalid regions in {}: {}.", path, e))
+    }
+
+    // Lists clashing givens, returns whether there were any.
+    pub fn report_conflicts(t: &Table) -> bool {
+        let conflicts = t.conflicts();
+        if !conflicts.is_empty() {
+            println!("Sudoku has clashing givens:");
+            for conflict in conflicts.iter() {
+                println!("  {}", conflict);
+            }
+        }
+        !conflicts.is_empty()
+    }
 
     pub fn table_from_stdin() -> Table {
         let t;
@@ -365,6 +1598,10 @@ pub mod app {
                     t = tb;
                     break;
                 }
+                Err(Error::Io(kind)) => {
+                    eprintln!("Error: couldn't read from stdin ({}).", kind);
+                    process::exit(1);
+                }
                 Err(e) => println!("Error: {}.\n Try again.", e),
             }
         }
@@ -0,0 +1,177 @@
+use crate::error::Error;
+use crate::grid::{Candidates, Table, Unit};
+use crate::solver::logic::{LogicSolver, Step, Technique, Uniqueness};
+use std::fmt;
+
+// What a puzzle gets when the logical solver runs out of techniques.
+pub const UNRATED: f32 = 11.0;
+
+// The bottom of the scale, for a grid with nothing left to deduce.
+pub const EASIEST: f32 = 1.0;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+    Extreme,
+}
+
+impl Difficulty {
+    pub fn from_score(score: f32) -> Difficulty {
+        match score {
+            s if s < 2.5 => Difficulty::Easy,
+            s if s < 4.0 => Difficulty::Medium,
+            s if s < 6.0 => Difficulty::Hard,
+            s if s < 8.0 => Difficulty::Expert,
+            _ => Difficulty::Extreme,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+            Difficulty::Extreme => "extreme",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rating {
+    // Sudoku Explainer style, one decimal, from EASIEST to UNRATED.
+    pub score: f32,
+    pub difficulty: Difficulty,
+    // None when no technique was needed or the solver got stuck.
+    pub hardest: Option<Technique>,
+    pub solved: bool,
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1} ({})", self.score, self.difficulty)
+    }
+}
+
+// Base values follow Sudoku Explainer where it has the technique.
+pub fn base_score(t: Technique) -> f32 {
+    match t {
+        Technique::HiddenSingle => 1.5,
+        Technique::NakedSingle => 2.3,
+        Technique::Pointing => 2.6,
+        Technique::BoxLineReduction => 2.8,
+        Technique::NakedPair => 3.0,
+        Technique::XWing => 3.2,
+        Technique::HiddenPair => 3.4,
+        Technique::FinnedXWing => 3.4,
+        Technique::NakedTriple => 3.6,
+        Technique::Swordfish => 3.8,
+        Technique::HiddenTriple => 4.0,
+        Technique::FinnedSwordfish => 4.0,
+        Technique::XYWing => 4.2,
+        Technique::XYZWing => 4.4,
+        Technique::WWing => 4.4,
+        Technique::UniqueRectangle1 | Technique::UniqueRectangle2 | Technique::UniqueRectangle4 => {
+            4.5
+        }
+        Technique::UniqueRectangle3
+        | Technique::UniqueRectangle5
+        | Technique::UniqueRectangle6
+        | Technique::HiddenUniqueRectangle => 4.6,
+        Technique::NakedQuad => 5.0,
+        Technique::Jellyfish => 5.2,
+        Technique::HiddenQuad => 5.4,
+        Technique::FinnedJellyfish => 5.4,
+        Technique::Bug1 => 5.6,
+        Technique::XChain => 6.5,
+        Technique::XYChain => 6.6,
+        Technique::Aic | Technique::NiceLoop => 7.0,
+        Technique::GroupedAic | Technique::GroupedNiceLoop => 7.3,
+    }
+}
+
+// Like Sudoku Explainer, a tenth more each time the chain outgrows the
+// next of 4, 6, 8, 12, 16, 24, ... nodes.
+fn length_bonus(nodes: usize) -> f32 {
+    let mut bonus = 0.0;
+    let (mut limit, mut even) = (4, true);
+    while nodes > limit {
+        bonus += 0.1;
+        limit = if even { limit * 3 / 2 } else { limit * 4 / 3 };
+        even = !even;
+    }
+    bonus
+}
+
+fn step_score(g: &Candidates, step: &Step) -> f32 {
+    match step.technique {
+        // Cheaper when the group alone gives it away.
+        Technique::HiddenSingle => {
+            let (r, c, d) = step.placements[0];
+            let group = Unit::Group(g.group_of(r, c));
+            let only = g
+                .cells_of(group)
+                .iter()
+                .all(|&(r2, c2)| (r2, c2) == (r, c) || !g.can_place(r2, c2, d));
+            if only {
+                1.2
+            } else {
+                1.5
+            }
+        }
+        Technique::XChain
+        | Technique::XYChain
+        | Technique::Aic
+        | Technique::NiceLoop
+        | Technique::GroupedAic
+        | Technique::GroupedNiceLoop => {
+            base_score(step.technique) + length_bonus(step.chain_length)
+        }
+        t => base_score(t),
+    }
+}
+
+// Every technique, easiest first by score.
+fn solver() -> LogicSolver {
+    let mut techniques = Technique::ALL.to_vec();
+    techniques.sort_by(|a, b| base_score(*a).total_cmp(&base_score(*b)));
+    let mut res = LogicSolver::with_techniques(&techniques);
+    res.set_uniqueness(Uniqueness::Verify);
+    res
+}
+
+// The hardest step of a solve that always takes the easiest technique at
+// hand. A puzzle that can't be rated is an error rather than a score:
+// clashing givens (Contradiction), no solution (NoSolution), several
+// solutions (NotUnique), or a diagonal grid (Unsupported).
+pub fn rate(t: &Table) -> Result<Rating, Error> {
+    let walkthrough = solver().solve(t)?;
+    let mut g = Candidates::new(t)?;
+    let (mut score, mut hardest) = (EASIEST, None);
+    for step in walkthrough.steps.iter() {
+        let s = step_score(&g, step);
+        if s > score {
+            score = s;
+            hardest = Some(step.technique);
+        }
+        step.apply(&mut g);
+    }
+    let solved = walkthrough.solved();
+    if !solved {
+        score = UNRATED;
+        hardest = None;
+    }
+    let score = (score * 10.0).round() / 10.0;
+    Ok(Rating {
+        score,
+        difficulty: Difficulty::from_score(score),
+        hardest,
+        solved,
+    })
+}
@@ -0,0 +1,193 @@
+use crate::error::Error;
+use crate::grid::{Candidates, Cell, Table};
+
+// Columns of the exact-cover matrix, `size * size` of each kind:
+// cell (r, c) is filled, row r has d, column c has d, group g has d. When
+// the diagonals count, `size` more for each: diagonal k has d.
+const ROOT: usize = 0;
+
+// Knuth's Dancing Links over a toroidal doubly linked list. Node 0 is the
+// root, the column headers follow, the rest are matrix ones.
+pub(crate) struct Dlx {
+    // Groups of the puzzle, and the grid solutions are written to.
+    blank: Table,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    col: Vec<usize>,
+    row: Vec<usize>,
+    size: Vec<usize>,
+    chosen: Vec<usize>,
+}
+
+fn row_id(size: usize, r: usize, c: usize, d: u8) -> usize {
+    (r * size + c) * size + (d as usize - 1)
+}
+
+fn from_row_id(size: usize, id: usize) -> (usize, usize, u8) {
+    (id / size / size, id / size % size, (id % size) as u8 + 1)
+}
+
+impl Dlx {
+    // Only digits still legal next to the givens get a row, so a given
+    // cell contributes exactly one.
+    pub(crate) fn new(t: &Table) -> Result<Dlx, Error> {
+        let cand = Candidates::new(t)?;
+        let size = t.size();
+        let diagonals = if t.diagonals() { 2 * size } else { 0 };
+        let columns = 4 * size * size + diagonals;
+        let mut res = Dlx {
+            blank: t.blank(),
+            left: (0..=columns)
+                .map(|i| (i + columns) % (columns + 1))
+                .collect(),
+            right: (0..=columns).map(|i| (i + 1) % (columns + 1)).collect(),
+            up: (0..=columns).collect(),
+            down: (0..=columns).collect(),
+            col: (0..=columns).collect(),
+            row: vec![usize::MAX; columns + 1],
+            size: vec![0; columns + 1],
+            chosen: vec![],
+        };
+        for r in 0..size {
+            for c in 0..size {
+                match t.grid[r][c] {
+                    Cell::Digit(d) => res.add_row(r, c, d),
+                    Cell::Empty => {
+                        for d in cand.digits(r, c) {
+                            res.add_row(r, c, d);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(res)
+    }
+
+    fn add_row(&mut self, r: usize, c: usize, d: u8) {
+        let size = self.blank.size();
+        let d_idx = d as usize - 1;
+        let group = self.blank.group_of(r, c);
+        let mut cols = vec![
+            r * size + c,
+            size * size + r * size + d_idx,
+            2 * size * size + c * size + d_idx,
+            3 * size * size + group * size + d_idx,
+        ];
+        if self.blank.diagonals() && r == c {
+            cols.push(4 * size * size + d_idx);
+        }
+        if self.blank.diagonals() && r + c + 1 == size {
+            cols.push(4 * size * size + size + d_idx);
+        }
+        let (first, end) = (self.col.len(), cols.len() - 1);
+        for (i, &column) in cols.iter().enumerate() {
+            let header = column + 1;
+            let node = first + i;
+            self.col.push(header);
+            self.row.push(row_id(size, r, c, d));
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            let last = self.up[header];
+            self.down[last] = node;
+            self.up[header] = node;
+            self.left.push(if i == 0 { first + end } else { node - 1 });
+            self.right.push(if i == end { first } else { node + 1 });
+            self.size[header] += 1;
+        }
+    }
+
+    fn cover(&mut self, header: usize) {
+        self.right[self.left[header]] = self.right[header];
+        self.left[self.right[header]] = self.left[header];
+        let mut i = self.down[header];
+        while i != header {
+            let mut j = self.right[i];
+            while j != i {
+                self.down[self.up[j]] = self.down[j];
+                self.up[self.down[j]] = self.up[j];
+                self.size[self.col[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, header: usize) {
+        let mut i = self.up[header];
+        while i != header {
+            let mut j = self.left[i];
+            while j != i {
+                self.size[self.col[j]] += 1;
+                self.down[self.up[j]] = j;
+                self.up[self.down[j]] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        self.right[self.left[header]] = header;
+        self.left[self.right[header]] = header;
+    }
+
+    // Calls `on_solution` with every exact cover found, the search stops
+    // as soon as it returns false. Returns whether the search was stopped.
+    pub(crate) fn search<F: FnMut(&Table) -> bool>(&mut self, on_solution: &mut F) -> bool {
+        if self.right[ROOT] == ROOT {
+            return !on_solution(&self.table());
+        }
+        // Branch on the column with the fewest remaining rows.
+        let mut header = self.right[ROOT];
+        let mut j = self.right[header];
+        while j != ROOT {
+            if self.size[j] < self.size[header] {
+                header = j;
+            }
+            j = self.right[j];
+        }
+        if self.size[header] == 0 {
+            return false;
+        }
+
+        self.cover(header);
+        let mut i = self.down[header];
+        let mut stopped = false;
+        while i != header && !stopped {
+            self.chosen.push(self.row[i]);
+            let mut j = self.right[i];
+            while j != i {
+                self.cover(self.col[j]);
+                j = self.right[j];
+            }
+            stopped = self.search(on_solution);
+            let mut j = self.left[i];
+            while j != i {
+                self.uncover(self.col[j]);
+                j = self.left[j];
+            }
+            self.chosen.pop();
+            i = self.down[i];
+        }
+        self.uncover(header);
+        stopped
+    }
+
+    fn table(&self) -> Table {
+        let mut t = self.blank.clone();
+        for &id in self.chosen.iter() {
+            let (r, c, d) = from_row_id(t.size(), id);
+            t.grid[r][c] = Cell::Digit(d);
+        }
+        t
+    }
+}
+
+pub fn solve_dlx(t: Table) -> Result<Table, Error> {
+    let mut dlx = Dlx::new(&t)?;
+    let mut res = None;
+    dlx.search(&mut |solution: &Table| {
+        res = Some(solution.clone());
+        false
+    });
+    res.ok_or(Error::NoSolution)
+}
@@ -0,0 +1,696 @@
+use super::count_solutions;
+use crate::error::Error;
+use crate::grid::{bit, mask_digits, Candidates, Table, Unit};
+use std::fmt;
+
+mod chains;
+mod fish;
+mod uniqueness;
+mod wings;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Technique {
+    HiddenSingle,
+    NakedSingle,
+    Pointing,
+    BoxLineReduction,
+    NakedPair,
+    HiddenPair,
+    NakedTriple,
+    HiddenTriple,
+    NakedQuad,
+    HiddenQuad,
+    XWing,
+    Swordfish,
+    Jellyfish,
+    FinnedXWing,
+    FinnedSwordfish,
+    FinnedJellyfish,
+    XYWing,
+    XYZWing,
+    WWing,
+    UniqueRectangle1,
+    UniqueRectangle2,
+    UniqueRectangle3,
+    UniqueRectangle4,
+    UniqueRectangle5,
+    UniqueRectangle6,
+    HiddenUniqueRectangle,
+    Bug1,
+    XChain,
+    XYChain,
+    Aic,
+    NiceLoop,
+    GroupedAic,
+    GroupedNiceLoop,
+}
+
+impl Technique {
+    // Default order of application, easiest first.
+    pub const ALL: &'static [Technique] = &[
+        Technique::HiddenSingle,
+        Technique::NakedSingle,
+        Technique::Pointing,
+        Technique::BoxLineReduction,
+        Technique::NakedPair,
+        Technique::HiddenPair,
+        Technique::NakedTriple,
+        Technique::HiddenTriple,
+        Technique::NakedQuad,
+        Technique::HiddenQuad,
+        Technique::XWing,
+        Technique::Swordfish,
+        Technique::Jellyfish,
+        Technique::FinnedXWing,
+        Technique::FinnedSwordfish,
+        Technique::FinnedJellyfish,
+        Technique::XYWing,
+        Technique::XYZWing,
+        Technique::WWing,
+        Technique::UniqueRectangle1,
+        Technique::UniqueRectangle2,
+        Technique::UniqueRectangle3,
+        Technique::UniqueRectangle4,
+        Technique::UniqueRectangle5,
+        Technique::UniqueRectangle6,
+        Technique::HiddenUniqueRectangle,
+        Technique::Bug1,
+        Technique::XChain,
+        Technique::XYChain,
+        Technique::Aic,
+        Technique::NiceLoop,
+        Technique::GroupedAic,
+        Technique::GroupedNiceLoop,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Technique::HiddenSingle => "Hidden single",
+            Technique::NakedSingle => "Naked single",
+            Technique::Pointing => "Pointing",
+            Technique::BoxLineReduction => "Box/line reduction",
+            Technique::NakedPair => "Naked pair",
+            Technique::HiddenPair => "Hidden pair",
+            Technique::NakedTriple => "Naked triple",
+            Technique::HiddenTriple => "Hidden triple",
+            Technique::NakedQuad => "Naked quad",
+            Technique::HiddenQuad => "Hidden quad",
+            Technique::XWing => "X-Wing",
+            Technique::Swordfish => "Swordfish",
+            Technique::Jellyfish => "Jellyfish",
+            Technique::FinnedXWing => "Finned X-Wing",
+            Technique::FinnedSwordfish => "Finned Swordfish",
+            Technique::FinnedJellyfish => "Finned Jellyfish",
+            Technique::XYWing => "XY-Wing",
+            Technique::XYZWing => "XYZ-Wing",
+            Technique::WWing => "W-Wing",
+            Technique::UniqueRectangle1 => "Unique Rectangle type 1",
+            Technique::UniqueRectangle2 => "Unique Rectangle type 2",
+            Technique::UniqueRectangle3 => "Unique Rectangle type 3",
+            Technique::UniqueRectangle4 => "Unique Rectangle type 4",
+            Technique::UniqueRectangle5 => "Unique Rectangle type 5",
+            Technique::UniqueRectangle6 => "Unique Rectangle type 6",
+            Technique::HiddenUniqueRectangle => "Hidden Unique Rectangle",
+            Technique::Bug1 => "BUG+1",
+            Technique::XChain => "X-Chain",
+            Technique::XYChain => "XY-Chain",
+            Technique::Aic => "AIC",
+            Technique::NiceLoop => "Nice Loop",
+            Technique::GroupedAic => "Grouped AIC",
+            Technique::GroupedNiceLoop => "Grouped Nice Loop",
+        }
+    }
+
+    // Only sound when the puzzle has a single solution.
+    pub fn needs_uniqueness(&self) -> bool {
+        matches!(
+            self,
+            Technique::UniqueRectangle1
+                | Technique::UniqueRectangle2
+                | Technique::UniqueRectangle3
+                | Technique::UniqueRectangle4
+                | Technique::UniqueRectangle5
+                | Technique::UniqueRectangle6
+                | Technique::HiddenUniqueRectangle
+                | Technique::Bug1
+        )
+    }
+
+    fn find(&self, g: &Candidates) -> Option<Step> {
+        match self {
+            Technique::HiddenSingle => hidden_single(g),
+            Technique::NakedSingle => naked_single(g),
+            Technique::Pointing => pointing(g),
+            Technique::BoxLineReduction => box_line_reduction(g),
+            Technique::NakedPair => naked_subset(g, 2),
+            Technique::HiddenPair => hidden_subset(g, 2),
+            Technique::NakedTriple => naked_subset(g, 3),
+            Technique::HiddenTriple => hidden_subset(g, 3),
+            Technique::NakedQuad => naked_subset(g, 4),
+            Technique::HiddenQuad => hidden_subset(g, 4),
+            Technique::XWing => fish::basic(g, 2),
+            Technique::Swordfish => fish::basic(g, 3),
+            Technique::Jellyfish => fish::basic(g, 4),
+            Technique::FinnedXWing => fish::finned(g, 2),
+            Technique::FinnedSwordfish => fish::finned(g, 3),
+            Technique::FinnedJellyfish => fish::finned(g, 4),
+            Technique::XYWing => wings::xy_wing(g),
+            Technique::XYZWing => wings::xyz_wing(g),
+            Technique::WWing => wings::w_wing(g),
+            Technique::UniqueRectangle1
+            | Technique::UniqueRectangle2
+            | Technique::UniqueRectangle3
+            | Technique::UniqueRectangle4
+            | Technique::UniqueRectangle5
+            | Technique::UniqueRectangle6
+            | Technique::HiddenUniqueRectangle
+            | Technique::Bug1 => uniqueness::find(g, *self),
+            Technique::XChain
+            | Technique::XYChain
+            | Technique::Aic
+            | Technique::NiceLoop
+            | Technique::GroupedAic
+            | Technique::GroupedNiceLoop => chains::find(g, *self),
+        }
+    }
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// One deduction: the cells it is based on and what it concludes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Step {
+    pub technique: Technique,
+    pub cells: Vec<(usize, usize)>,
+    // Nodes the chain runs through, zero for techniques without one.
+    pub chain_length: usize,
+    pub placements: Vec<(usize, usize, u8)>,
+    pub eliminations: Vec<(usize, usize, u8)>,
+    pub description: String,
+}
+
+impl Step {
+    pub fn apply(&self, g: &mut Candidates) {
+        for &(r, c, d) in self.placements.iter() {
+            if g.value(r, c).is_none() {
+                g.place(r, c, d);
+            }
+        }
+        for &(r, c, d) in self.eliminations.iter() {
+            g.eliminate(r, c, d);
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+// Where the logical solve got to: the grid is complete unless the
+// techniques ran out of deductions.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Walkthrough {
+    pub table: Table,
+    pub steps: Vec<Step>,
+}
+
+impl Walkthrough {
+    pub fn solved(&self) -> bool {
+        self.table.empty_cells().is_empty()
+    }
+
+    // Distinct techniques the solve needed, easiest first.
+    pub fn techniques(&self) -> Vec<Technique> {
+        let mut res: Vec<Technique> = self.steps.iter().map(|s| s.technique).collect();
+        res.sort_unstable();
+        res.dedup();
+        res
+    }
+}
+
+// Whether techniques relying on a single solution may be used.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Uniqueness {
+    #[default]
+    Ignore,
+    Assume,
+    // Counts solutions first, failing with NotUnique on several.
+    Verify,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LogicSolver {
+    techniques: Vec<Technique>,
+    uniqueness: Uniqueness,
+}
+
+impl Default for LogicSolver {
+    fn default() -> Self {
+        LogicSolver::new()
+    }
+}
+
+impl LogicSolver {
+    pub fn new() -> LogicSolver {
+        LogicSolver::with_techniques(Technique::ALL)
+    }
+
+    // Techniques are tried in the given order.
+    pub fn with_techniques(techniques: &[Technique]) -> LogicSolver {
+        LogicSolver {
+            techniques: techniques.to_vec(),
+            uniqueness: Uniqueness::Ignore,
+        }
+    }
+
+    pub fn uniqueness(&self) -> Uniqueness {
+        self.uniqueness
+    }
+
+    pub fn set_uniqueness(&mut self, u: Uniqueness) {
+        self.uniqueness = u;
+    }
+
+    pub fn techniques(&self) -> &[Technique] {
+        &self.techniques
+    }
+
+    // Keeps the default order among enabled techniques.
+    pub fn enable(&mut self, t: Technique) {
+        if !self.techniques.contains(&t) {
+            self.techniques.push(t);
+            self.techniques.sort_unstable();
+        }
+    }
+
+    pub fn disable(&mut self, t: Technique) {
+        self.techniques.retain(|&other| other != t);
+    }
+
+    // Groups may be boxes or jigsaw regions, but the techniques don't know
+    // the diagonals: there is never a step on a diagonal grid.
+    pub fn next_step(&self, g: &Candidates) -> Option<Step> {
+        if g.diagonals() {
+            return None;
+        }
+        let allowed =
+            |t: &&Technique| self.uniqueness != Uniqueness::Ignore || !t.needs_uniqueness();
+        self.techniques
+            .iter()
+            .filter(allowed)
+            .find_map(|t| t.find(g))
+    }
+
+    pub fn solve(&self, t: &Table) -> Result<Walkthrough, Error> {
+        if t.diagonals() {
+            let variant = "diagonal".to_string();
+            return Err(Error::Unsupported { variant });
+        }
+        let mut g = Candidates::new(t)?;
+        if self.uniqueness == Uniqueness::Verify {
+            match count_solutions(t, 2) {
+                0 => return Err(Error::NoSolution),
+                1 => {}
+                _ => return Err(Error::NotUnique),
+            }
+        }
+        let mut steps = vec![];
+        loop {
+            if broken(&g) {
+                return Err(Error::NoSolution);
+            }
+            match self.next_step(&g) {
+                Some(step) => {
+                    step.apply(&mut g);
+                    steps.push(step);
+                }
+                None => break,
+            }
+        }
+        Ok(Walkthrough {
+            table: g.to_table(),
+            steps,
+        })
+    }
+}
+
+pub fn solve_logic(t: &Table) -> Result<Walkthrough, Error> {
+    LogicSolver::new().solve(t)
+}
+
+// Every digit of the grid.
+pub(crate) fn all_digits(g: &Candidates) -> std::ops::RangeInclusive<u8> {
+    1..=g.size() as u8
+}
+
+// Rows, then columns.
+pub(crate) fn lines(g: &Candidates) -> Vec<Unit> {
+    let size = g.size();
+    (0..size)
+        .map(Unit::Row)
+        .chain((0..size).map(Unit::Col))
+        .collect()
+}
+
+pub(crate) fn open_cells(g: &Candidates) -> Vec<(usize, usize)> {
+    let size = g.size();
+    (0..size)
+        .flat_map(|r| (0..size).map(move |c| (r, c)))
+        .filter(|&(r, c)| g.value(r, c).is_none())
+        .collect()
+}
+
+// A cell without candidates, or a digit without a place in some unit.
+fn broken(g: &Candidates) -> bool {
+    if open_cells(g).iter().any(|&(r, c)| g.mask(r, c) == 0) {
+        return true;
+    }
+    g.units().into_iter().any(|unit| {
+        let cells = g.cells_of(unit);
+        all_digits(g).any(|d| {
+            cells
+                .iter()
+                .all(|&(r, c)| g.value(r, c) != Some(d) && !g.can_place(r, c, d))
+        })
+    })
+}
+
+pub(crate) fn sees(g: &Candidates, a: (usize, usize), b: (usize, usize)) -> bool {
+    a != b && (a.0 == b.0 || a.1 == b.1 || g.group_of(a.0, a.1) == g.group_of(b.0, b.1))
+}
+
+pub(crate) fn cell_name(r: usize, c: usize) -> String {
+    format!("r{}c{}", r + 1, c + 1)
+}
+
+pub(crate) fn cell_list(cells: &[(usize, usize)]) -> String {
+    cells
+        .iter()
+        .map(|&(r, c)| cell_name(r, c))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub(crate) fn digit_list(mask: u32) -> String {
+    mask_digits(mask)
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// "removes 3 from r1c2, r1c5 and 7 from r1c5", digit by digit.
+pub(crate) fn describe_eliminations(eliminations: &[(usize, usize, u8)]) -> String {
+    let mut digits: Vec<u8> = eliminations.iter().map(|e| e.2).collect();
+    digits.sort_unstable();
+    digits.dedup();
+    let mut parts = vec![];
+    for d in digits {
+        let cells: Vec<(usize, usize)> = eliminations
+            .iter()
+            .filter(|e| e.2 == d)
+            .map(|&(r, c, _)| (r, c))
+            .collect();
+        if !cells.is_empty() {
+            parts.push(format!("{} from {}", d, cell_list(&cells)));
+        }
+    }
+    format!("removes {}", parts.join(" and "))
+}
+
+// Cells of `unit` still holding candidate `d`.
+pub(crate) fn spots(g: &Candidates, unit: &Unit, d: u8) -> Vec<(usize, usize)> {
+    g.cells_of(*unit)
+        .into_iter()
+        .filter(|&(r, c)| g.can_place(r, c, d))
+        .collect()
+}
+
+// Eliminations of `d` from every cell that sees all of `cells`.
+pub(crate) fn seen_by_all(
+    g: &Candidates,
+    cells: &[(usize, usize)],
+    d: u8,
+) -> Vec<(usize, usize, u8)> {
+    open_cells(g)
+        .into_iter()
+        .filter(|&(r, c)| g.can_place(r, c, d))
+        .filter(|&cell| cells.iter().all(|&other| sees(g, cell, other)))
+        .map(|(r, c)| (r, c, d))
+        .collect()
+}
+
+pub(crate) fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    fn go(start: usize, n: usize, k: usize, cur: &mut Vec<usize>, res: &mut Vec<Vec<usize>>) {
+        if cur.len() == k {
+            res.push(cur.clone());
+            return;
+        }
+        for i in start..n {
+            cur.push(i);
+            go(i + 1, n, k, cur, res);
+            cur.pop();
+        }
+    }
+    let mut res = vec![];
+    go(0, n, k, &mut vec![], &mut res);
+    res
+}
+
+fn naked_single(g: &Candidates) -> Option<Step> {
+    let (r, c) = open_cells(g)
+        .into_iter()
+        .find(|&(r, c)| g.count(r, c) == 1)?;
+    let d = g.digits(r, c)[0];
+    Some(Step {
+        technique: Technique::NakedSingle,
+        cells: vec![(r, c)],
+        chain_length: 0,
+        placements: vec![(r, c, d)],
+        eliminations: vec![],
+        description: format!("Naked single: {} can only be {}", cell_name(r, c), d),
+    })
+}
+
+fn hidden_single(g: &Candidates) -> Option<Step> {
+    // Groups first, they are the easiest to spot.
+    let mut units = g.units();
+    units.rotate_left(2 * g.size());
+    for unit in units {
+        for d in all_digits(g) {
+            if let [(r, c)] = spots(g, &unit, d)[..] {
+                return Some(Step {
+                    technique: Technique::HiddenSingle,
+                    cells: vec![(r, c)],
+                    chain_length: 0,
+                    placements: vec![(r, c, d)],
+                    eliminations: vec![],
+                    description: format!(
+                        "Hidden single: {} is the only place for {} in {}",
+                        cell_name(r, c),
+                        d,
+                        unit
+                    ),
+                });
+            }
+        }
+    }
+    None
+}
+
+fn naked_subset(g: &Candidates, n: usize) -> Option<Step> {
+    let technique = match n {
+        2 => Technique::NakedPair,
+        3 => Technique::NakedTriple,
+        _ => Technique::NakedQuad,
+    };
+    for unit in g.units() {
+        let open: Vec<(usize, usize)> = g
+            .cells_of(unit)
+            .into_iter()
+            .filter(|&(r, c)| g.value(r, c).is_none())
+            .collect();
+        let small: Vec<(usize, usize)> = open
+            .iter()
+            .copied()
+            .filter(|&(r, c)| (g.count(r, c) as usize) <= n)
+            .collect();
+        for combo in combinations(small.len(), n) {
+            let cells: Vec<(usize, usize)> = combo.iter().map(|&i| small[i]).collect();
+            let mask = cells.iter().fold(0, |m, &(r, c)| m | g.mask(r, c));
+            if mask.count_ones() as usize != n {
+                continue;
+            }
+            let mut eliminations = vec![];
+            for &(r, c) in open.iter().filter(|cell| !cells.contains(cell)) {
+                for d in mask_digits(mask & g.mask(r, c)) {
+                    eliminations.push((r, c, d));
+                }
+            }
+            if !eliminations.is_empty() {
+                let description = format!(
+                    "{}: {} in {} are limited to {}, which {}",
+                    technique,
+                    cell_list(&cells),
+                    unit,
+                    digit_list(mask),
+                    describe_eliminations(&eliminations)
+                );
+                return Some(Step {
+                    technique,
+                    cells,
+                    chain_length: 0,
+                    placements: vec![],
+                    eliminations,
+                    description,
+                });
+            }
+        }
+    }
+    None
+}
+
+fn hidden_subset(g: &Candidates, n: usize) -> Option<Step> {
+    let technique = match n {
+        2 => Technique::HiddenPair,
+        3 => Technique::HiddenTriple,
+        _ => Technique::HiddenQuad,
+    };
+    for unit in g.units() {
+        let cells = g.cells_of(unit);
+        let open_digits: Vec<u8> = all_digits(g)
+            .filter(|&d| cells.iter().all(|&(r, c)| g.value(r, c) != Some(d)))
+            .collect();
+        let small: Vec<u8> = open_digits
+            .iter()
+            .copied()
+            .filter(|&d| spots(g, &unit, d).len() <= n)
+            .collect();
+        for combo in combinations(small.len(), n) {
+            let mask = combo.iter().fold(0, |m, &i| m | bit(small[i]));
+            let places: Vec<(usize, usize)> = cells
+                .iter()
+                .copied()
+                .filter(|&(r, c)| g.mask(r, c) & mask != 0)
+                .collect();
+            if places.len() != n {
+                continue;
+            }
+            let mut eliminations = vec![];
+            for &(r, c) in places.iter() {
+                for d in mask_digits(g.mask(r, c) & !mask) {
+                    eliminations.push((r, c, d));
+                }
+            }
+            if !eliminations.is_empty() {
+                let description = format!(
+                    "{}: {} only fit in {} within {}, which {}",
+                    technique,
+                    digit_list(mask),
+                    cell_list(&places),
+                    unit,
+                    describe_eliminations(&eliminations)
+                );
+                return Some(Step {
+                    technique,
+                    cells: places,
+                    chain_length: 0,
+                    placements: vec![],
+                    eliminations,
+                    description,
+                });
+            }
+        }
+    }
+    None
+}
+
+// A digit confined to one line inside a group is removed from the rest of
+// that line.
+fn pointing(g: &Candidates) -> Option<Step> {
+    for gi in 0..g.size() {
+        let group = Unit::Group(gi);
+        for d in all_digits(g) {
+            let cells = spots(g, &group, d);
+            if cells.len() < 2 {
+                continue;
+            }
+            let line = if cells.iter().all(|&(r, _)| r == cells[0].0) {
+                Unit::Row(cells[0].0)
+            } else if cells.iter().all(|&(_, c)| c == cells[0].1) {
+                Unit::Col(cells[0].1)
+            } else {
+                continue;
+            };
+            let eliminations: Vec<(usize, usize, u8)> = spots(g, &line, d)
+                .into_iter()
+                .filter(|&(r, c)| g.group_of(r, c) != gi)
+                .map(|(r, c)| (r, c, d))
+                .collect();
+            if !eliminations.is_empty() {
+                let description = format!(
+                    "Pointing: {} in {} only fits in {}, which {}",
+                    d,
+                    group,
+                    line,
+                    describe_eliminations(&eliminations)
+                );
+                return Some(Step {
+                    technique: Technique::Pointing,
+                    cells,
+                    chain_length: 0,
+                    placements: vec![],
+                    eliminations,
+                    description,
+                });
+            }
+        }
+    }
+    None
+}
+
+// A digit confined to one group inside a line is removed from the rest of
+// that group.
+fn box_line_reduction(g: &Candidates) -> Option<Step> {
+    for line in lines(g) {
+        for d in all_digits(g) {
+            let cells = spots(g, &line, d);
+            if cells.len() < 2 {
+                continue;
+            }
+            let gi = g.group_of(cells[0].0, cells[0].1);
+            if cells.iter().any(|&(r, c)| g.group_of(r, c) != gi) {
+                continue;
+            }
+            let group = Unit::Group(gi);
+            let eliminations: Vec<(usize, usize, u8)> = spots(g, &group, d)
+                .into_iter()
+                .filter(|cell| !cells.contains(cell))
+                .map(|(r, c)| (r, c, d))
+                .collect();
+            if !eliminations.is_empty() {
+                let description = format!(
+                    "Box/line reduction: {} in {} only fits in {}, which {}",
+                    d,
+                    line,
+                    group,
+                    describe_eliminations(&eliminations)
+                );
+                return Some(Step {
+                    technique: Technique::BoxLineReduction,
+                    cells,
+                    chain_length: 0,
+                    placements: vec![],
+                    eliminations,
+                    description,
+                });
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,357 @@
+use super::{all_digits, cell_name, describe_eliminations, lines, open_cells};
+use super::{seen_by_all, sees, Step, Technique};
+use crate::grid::Candidates;
+use std::collections::{HashMap, VecDeque};
+
+// A candidate, or a digit spread over the cells where a line crosses a group.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Node {
+    digit: u8,
+    cells: Vec<(usize, usize)>,
+}
+
+struct Graph {
+    nodes: Vec<Node>,
+    // If the node is false the other one is true.
+    strong: Vec<Vec<usize>>,
+    // If the node is true the other one is false.
+    weak: Vec<Vec<usize>>,
+}
+
+// What a technique may link and conclude.
+struct Rules {
+    grouped: bool,
+    // Links never leave a digit.
+    one_digit: bool,
+    // Strong links only inside bivalue cells.
+    bivalue: bool,
+    loops: bool,
+}
+
+fn rules(technique: Technique) -> Rules {
+    let (grouped, one_digit, bivalue, loops) = match technique {
+        Technique::XChain => (false, true, false, false),
+        Technique::XYChain => (false, false, true, false),
+        Technique::Aic => (false, false, false, false),
+        Technique::NiceLoop => (false, false, false, true),
+        Technique::GroupedAic => (true, false, false, false),
+        _ => (true, false, false, true),
+    };
+    Rules {
+        grouped,
+        one_digit,
+        bivalue,
+        loops,
+    }
+}
+
+fn build(g: &Candidates, rules: &Rules) -> Graph {
+    let mut nodes = vec![];
+    for (r, c) in open_cells(g) {
+        for d in g.digits(r, c) {
+            nodes.push(Node {
+                digit: d,
+                cells: vec![(r, c)],
+            });
+        }
+    }
+    let singles = nodes.len();
+    if rules.grouped {
+        for line in lines(g) {
+            for gi in 0..g.size() {
+                let cells: Vec<(usize, usize)> = g
+                    .cells_of(line)
+                    .into_iter()
+                    .filter(|&(r, c)| g.group_of(r, c) == gi)
+                    .collect();
+                for d in all_digits(g) {
+                    let spots: Vec<(usize, usize)> = cells
+                        .iter()
+                        .copied()
+                        .filter(|&(r, c)| g.can_place(r, c, d))
+                        .collect();
+                    if spots.len() > 1 {
+                        nodes.push(Node {
+                            digit: d,
+                            cells: spots,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    let index: HashMap<(u8, Vec<(usize, usize)>), usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| ((n.digit, n.cells.clone()), i))
+        .collect();
+
+    let mut strong = vec![vec![]; nodes.len()];
+    let mut weak = vec![vec![]; nodes.len()];
+    // Inside a cell.
+    for i in 0..singles {
+        for j in 0..singles {
+            let (a, b) = (&nodes[i], &nodes[j]);
+            if i == j || a.cells != b.cells || rules.one_digit {
+                continue;
+            }
+            if !rules.bivalue {
+                weak[i].push(j);
+            }
+            let (r, c) = a.cells[0];
+            if g.count(r, c) == 2 {
+                strong[i].push(j);
+            }
+        }
+    }
+    // Same digit in one unit: the unit's spots split into two nodes.
+    if !rules.bivalue {
+        for unit in g.units() {
+            for d in all_digits(g) {
+                let spots = super::spots(g, &unit, d);
+                for (i, a) in nodes.iter().enumerate() {
+                    if a.digit != d || !a.cells.iter().all(|cell| spots.contains(cell)) {
+                        continue;
+                    }
+                    let rest: Vec<(usize, usize)> = spots
+                        .iter()
+                        .copied()
+                        .filter(|cell| !a.cells.contains(cell))
+                        .collect();
+                    if let Some(&j) = index.get(&(d, rest)) {
+                        if !strong[i].contains(&j) {
+                            strong[i].push(j);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    for (i, a) in nodes.iter().enumerate() {
+        for (j, b) in nodes.iter().enumerate() {
+            if a.digit == b.digit
+                && a.cells
+                    .iter()
+                    .all(|&x| b.cells.iter().all(|&y| sees(g, x, y)))
+            {
+                weak[i].push(j);
+            }
+        }
+    }
+    Graph {
+        nodes,
+        strong,
+        weak,
+    }
+}
+
+// Eliminations following from at least one of `a` and `b` being true.
+fn either(g: &Candidates, a: &Node, b: &Node) -> Vec<(usize, usize, u8)> {
+    if a.digit == b.digit {
+        return seen_by_all(g, &[a.cells.as_slice(), &b.cells].concat(), a.digit);
+    }
+    if a.cells.len() > 1 || b.cells.len() > 1 {
+        return vec![];
+    }
+    let ((ar, ac), (br, bc)) = (a.cells[0], b.cells[0]);
+    if (ar, ac) == (br, bc) {
+        return g
+            .digits(ar, ac)
+            .into_iter()
+            .filter(|&d| d != a.digit && d != b.digit)
+            .map(|d| (ar, ac, d))
+            .collect();
+    }
+    let mut res = vec![];
+    if sees(g, (ar, ac), (br, bc)) {
+        if g.can_place(ar, ac, b.digit) {
+            res.push((ar, ac, b.digit));
+        }
+        if g.can_place(br, bc, a.digit) {
+            res.push((br, bc, a.digit));
+        }
+    }
+    res
+}
+
+fn overlap(a: &Node, b: &Node) -> bool {
+    a.digit == b.digit && a.cells.iter().any(|cell| b.cells.contains(cell))
+}
+
+fn cells_name(cells: &[(usize, usize)]) -> String {
+    if cells.len() == 1 {
+        return cell_name(cells[0].0, cells[0].1);
+    }
+    let rows: String = cells.iter().map(|&(r, _)| (r + 1).to_string()).collect();
+    let cols: String = cells.iter().map(|&(_, c)| (c + 1).to_string()).collect();
+    if cells.iter().all(|&(r, _)| r == cells[0].0) {
+        format!("r{}c{}", cells[0].0 + 1, cols)
+    } else {
+        format!("r{}c{}", rows, cells[0].1 + 1)
+    }
+}
+
+// Eureka notation, links alternating strong (=) and weak (-). Candidates
+// of one cell share a term: (5=3)r1c2.
+fn eureka(chain: &[&Node], closed: bool) -> String {
+    let mut res = String::new();
+    let mut i = 0;
+    while i < chain.len() {
+        if i > 0 {
+            res.push(if i % 2 == 1 { '=' } else { '-' });
+        }
+        res.push('(');
+        res.push_str(&chain[i].digit.to_string());
+        while i + 1 < chain.len() && chain[i + 1].cells == chain[i].cells {
+            res.push(if i % 2 == 0 { '=' } else { '-' });
+            res.push_str(&chain[i + 1].digit.to_string());
+            i += 1;
+        }
+        res.push(')');
+        res.push_str(&cells_name(&chain[i].cells));
+        i += 1;
+    }
+    if closed {
+        res.push('-');
+        res.push_str(&format!(
+            "({}){}",
+            chain[0].digit,
+            cells_name(&chain[0].cells)
+        ));
+    }
+    res
+}
+
+struct Found {
+    chain: Vec<usize>,
+    closed: bool,
+    placements: Vec<(usize, usize, u8)>,
+    eliminations: Vec<(usize, usize, u8)>,
+}
+
+fn conclude(g: &Candidates, graph: &Graph, rules: &Rules, chain: &[usize]) -> Option<Found> {
+    let nodes = &graph.nodes;
+    let (s, e) = (chain[0], chain[chain.len() - 1]);
+    let found = |closed, placements, eliminations| Found {
+        chain: chain.to_vec(),
+        closed,
+        placements,
+        eliminations,
+    };
+    if !rules.loops {
+        if chain.len() < 4 || (rules.one_digit || rules.bivalue) && nodes[s].digit != nodes[e].digit
+        {
+            return None;
+        }
+        let eliminations = either(g, &nodes[s], &nodes[e]);
+        return (!eliminations.is_empty()).then(|| found(false, vec![], eliminations));
+    }
+    // The start being false makes it true.
+    if s == e {
+        let (r, c) = nodes[s].cells[0];
+        if nodes[s].cells.len() == 1 {
+            return Some(found(false, vec![(r, c, nodes[s].digit)], vec![]));
+        }
+        return None;
+    }
+    // Continuous loop: every weak link turns strong.
+    let distinct = chain.iter().enumerate().all(|(i, &a)| {
+        chain[i + 1..]
+            .iter()
+            .all(|&b| !overlap(&nodes[a], &nodes[b]))
+    });
+    if chain.len() < 4 || !graph.weak[e].contains(&s) || !distinct {
+        return None;
+    }
+    let mut eliminations = vec![];
+    let closing = [e, s];
+    let weak_links = chain[1..].chunks(2).filter(|w| w.len() == 2);
+    for w in weak_links.chain([&closing[..]]) {
+        for x in either(g, &nodes[w[0]], &nodes[w[1]]) {
+            let in_chain = chain
+                .iter()
+                .any(|&n| nodes[n].digit == x.2 && nodes[n].cells.contains(&(x.0, x.1)));
+            if !in_chain && !eliminations.contains(&x) {
+                eliminations.push(x);
+            }
+        }
+    }
+    (!eliminations.is_empty()).then(|| found(true, vec![], eliminations))
+}
+
+// Breadth first from every node assumed false, so each start yields its
+// shortest productive chain; the shortest over all starts wins.
+fn search(g: &Candidates, graph: &Graph, rules: &Rules) -> Option<Found> {
+    let n = graph.nodes.len();
+    let mut best: Option<Found> = None;
+    for start in 0..n {
+        // State 2 * node + 1 means the node is true.
+        let mut parent = vec![usize::MAX; 2 * n];
+        let mut queue = VecDeque::from([2 * start]);
+        parent[2 * start] = 2 * start;
+        while let Some(state) = queue.pop_front() {
+            let (node, on) = (state / 2, state % 2 == 1);
+            if on && (node != start || rules.loops) {
+                let mut chain = vec![node];
+                let mut cur = state;
+                while cur != 2 * start {
+                    cur = parent[cur];
+                    chain.push(cur / 2);
+                }
+                chain.reverse();
+                if best.as_ref().is_some_and(|b| b.chain.len() <= chain.len()) {
+                    break;
+                }
+                if let Some(found) = conclude(g, graph, rules, &chain) {
+                    best = Some(found);
+                    break;
+                }
+            }
+            let next = if on {
+                &graph.weak[node]
+            } else {
+                &graph.strong[node]
+            };
+            for &other in next.iter() {
+                let s = 2 * other + usize::from(!on);
+                if parent[s] == usize::MAX {
+                    parent[s] = state;
+                    queue.push_back(s);
+                }
+            }
+        }
+    }
+    best
+}
+
+pub(super) fn find(g: &Candidates, technique: Technique) -> Option<Step> {
+    let rules = rules(technique);
+    let graph = build(g, &rules);
+    let found = search(g, &graph, &rules)?;
+    let chain: Vec<&Node> = found.chain.iter().map(|&i| &graph.nodes[i]).collect();
+    let mut cells: Vec<(usize, usize)> = vec![];
+    for node in chain.iter() {
+        for &cell in node.cells.iter() {
+            if !cells.contains(&cell) {
+                cells.push(cell);
+            }
+        }
+    }
+    let conclusion = match found.placements.first() {
+        Some(&(r, c, d)) => format!("so {} is {}", cell_name(r, c), d),
+        None => format!("which {}", describe_eliminations(&found.eliminations)),
+    };
+    Some(Step {
+        technique,
+        cells,
+        chain_length: found.chain.len(),
+        placements: found.placements,
+        eliminations: found.eliminations,
+        description: format!(
+            "{}: {}, {}",
+            technique,
+            eureka(&chain, found.closed),
+            conclusion
+        ),
+    })
+}
@@ -0,0 +1,233 @@
+use super::{all_digits, cell_list, combinations, describe_eliminations, Step, Technique};
+use crate::grid::Candidates;
+
+fn name(n: usize) -> &'static str {
+    match n {
+        2 => "X-Wing",
+        3 => "Swordfish",
+        _ => "Jellyfish",
+    }
+}
+
+// Cell at position `pos` of line `line`, lines being rows or columns.
+fn at(by_rows: bool, line: usize, pos: usize) -> (usize, usize) {
+    if by_rows {
+        (line, pos)
+    } else {
+        (pos, line)
+    }
+}
+
+fn line_name(by_rows: bool) -> &'static str {
+    if by_rows {
+        "rows"
+    } else {
+        "columns"
+    }
+}
+
+fn numbers(v: &[usize]) -> String {
+    v.iter()
+        .map(|i| (i + 1).to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Positions of candidate `d` along every line.
+fn positions(g: &Candidates, by_rows: bool, d: u8) -> Vec<Vec<usize>> {
+    let size = g.size();
+    (0..size)
+        .map(|line| {
+            (0..size)
+                .filter(|&pos| {
+                    let (r, c) = at(by_rows, line, pos);
+                    g.can_place(r, c, d)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// `n` base lines whose candidates for a digit lie on `n` cover lines: the
+// digit is gone from the rest of the cover lines.
+pub(super) fn basic(g: &Candidates, n: usize) -> Option<Step> {
+    let technique = match n {
+        2 => Technique::XWing,
+        3 => Technique::Swordfish,
+        _ => Technique::Jellyfish,
+    };
+    for by_rows in [true, false] {
+        for d in all_digits(g) {
+            let pos = positions(g, by_rows, d);
+            let lines: Vec<usize> = (0..pos.len())
+                .filter(|&l| (2..=n).contains(&pos[l].len()))
+                .collect();
+            for combo in combinations(lines.len(), n) {
+                let base: Vec<usize> = combo.iter().map(|&i| lines[i]).collect();
+                let mut cover: Vec<usize> = base.iter().flat_map(|&l| pos[l].clone()).collect();
+                cover.sort_unstable();
+                cover.dedup();
+                if cover.len() != n {
+                    continue;
+                }
+                let eliminations: Vec<(usize, usize, u8)> = (0..pos.len())
+                    .filter(|l| !base.contains(l))
+                    .flat_map(|l| cover.iter().map(move |&p| (l, p)))
+                    .filter(|&(l, p)| pos[l].contains(&p))
+                    .map(|(l, p)| {
+                        let (r, c) = at(by_rows, l, p);
+                        (r, c, d)
+                    })
+                    .collect();
+                if eliminations.is_empty() {
+                    continue;
+                }
+                let cells: Vec<(usize, usize)> = base
+                    .iter()
+                    .flat_map(|&l| pos[l].iter().map(move |&p| at(by_rows, l, p)))
+                    .collect();
+                let description = format!(
+                    "{}: {} in {} {} is confined to {} {}, which {}",
+                    name(n),
+                    d,
+                    line_name(by_rows),
+                    numbers(&base),
+                    line_name(!by_rows),
+                    numbers(&cover),
+                    describe_eliminations(&eliminations)
+                );
+                return Some(Step {
+                    technique,
+                    cells,
+                    chain_length: 0,
+                    placements: vec![],
+                    eliminations,
+                    description,
+                });
+            }
+        }
+    }
+    None
+}
+
+// A fish with extra candidates (fins) in base lines, all inside one group.
+// Either a fin is true or the fish is, so cover cells in the fins' group
+// lose the digit. It is sashimi when a base line has a single cover cell.
+pub(super) fn finned(g: &Candidates, n: usize) -> Option<Step> {
+    let technique = match n {
+        2 => Technique::FinnedXWing,
+        3 => Technique::FinnedSwordfish,
+        _ => Technique::FinnedJellyfish,
+    };
+    for by_rows in [true, false] {
+        for d in all_digits(g) {
+            let pos = positions(g, by_rows, d);
+            let lines: Vec<usize> = (0..pos.len()).filter(|&l| !pos[l].is_empty()).collect();
+            for combo in combinations(lines.len(), n) {
+                let base: Vec<usize> = combo.iter().map(|&i| lines[i]).collect();
+                let mut union: Vec<usize> = base.iter().flat_map(|&l| pos[l].clone()).collect();
+                union.sort_unstable();
+                union.dedup();
+                // Fins sit in one group, so they span at most its width
+                // along the base lines.
+                if union.len() <= n || union.len() > n + fin_span(g, by_rows) {
+                    continue;
+                }
+                for cover_combo in combinations(union.len(), n) {
+                    let cover: Vec<usize> = cover_combo.iter().map(|&i| union[i]).collect();
+                    let step = finned_with(g, &pos, by_rows, d, &base, &cover, technique);
+                    if let Some(step) = step {
+                        return Some(step);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+// Positions a group covers along a row, or along a column. Jigsaw
+// regions may stretch across the whole line.
+fn fin_span(g: &Candidates, by_rows: bool) -> usize {
+    let shape = g.shape();
+    if g.regions().is_some() {
+        g.size()
+    } else if by_rows {
+        shape.box_cols()
+    } else {
+        shape.box_rows()
+    }
+}
+
+fn finned_with(
+    g: &Candidates,
+    pos: &[Vec<usize>],
+    by_rows: bool,
+    d: u8,
+    base: &[usize],
+    cover: &[usize],
+    technique: Technique,
+) -> Option<Step> {
+    if base
+        .iter()
+        .any(|&l| !pos[l].iter().any(|p| cover.contains(p)))
+    {
+        return None;
+    }
+    let fins: Vec<(usize, usize)> = base
+        .iter()
+        .flat_map(|&l| {
+            pos[l]
+                .iter()
+                .filter(|p| !cover.contains(p))
+                .map(move |&p| at(by_rows, l, p))
+        })
+        .collect();
+    let fin_group = g.group_of(fins[0].0, fins[0].1);
+    if fins.iter().any(|&(r, c)| g.group_of(r, c) != fin_group) {
+        return None;
+    }
+    let eliminations: Vec<(usize, usize, u8)> = (0..pos.len())
+        .filter(|l| !base.contains(l))
+        .flat_map(|l| cover.iter().map(move |&p| (l, p)))
+        .filter(|&(l, p)| pos[l].contains(&p))
+        .map(|(l, p)| at(by_rows, l, p))
+        .filter(|&(r, c)| g.group_of(r, c) == fin_group)
+        .map(|(r, c)| (r, c, d))
+        .collect();
+    if eliminations.is_empty() {
+        return None;
+    }
+    let sashimi = base
+        .iter()
+        .any(|&l| pos[l].iter().filter(|p| cover.contains(p)).count() < 2);
+    let cells: Vec<(usize, usize)> = base
+        .iter()
+        .flat_map(|&l| {
+            pos[l]
+                .iter()
+                .filter(|p| cover.contains(p))
+                .map(move |&p| at(by_rows, l, p))
+        })
+        .collect();
+    let description = format!(
+        "Finned {}{}: {} in {} {} is confined to {} {} with fins at {}, which {}",
+        name(cover.len()),
+        if sashimi { " (sashimi)" } else { "" },
+        d,
+        line_name(by_rows),
+        numbers(base),
+        line_name(!by_rows),
+        numbers(cover),
+        cell_list(&fins),
+        describe_eliminations(&eliminations)
+    );
+    Some(Step {
+        technique,
+        cells: cells.into_iter().chain(fins).collect(),
+        chain_length: 0,
+        placements: vec![],
+        eliminations,
+        description,
+    })
+}
@@ -0,0 +1,313 @@
+use super::{all_digits, cell_list, cell_name, combinations, describe_eliminations, digit_list};
+use super::{open_cells, seen_by_all, spots, Step, Technique};
+use crate::grid::{bit, mask_digits, Candidates, Unit};
+
+// Four open cells on two rows, two columns and two groups, all holding
+// the digits a and b. With a single solution they can't end up as a and b
+// only, which would let the pair swap. Floor cells hold nothing else.
+struct Rectangle {
+    cells: [(usize, usize); 4],
+    pair: u32,
+    floor: Vec<(usize, usize)>,
+    roof: Vec<(usize, usize)>,
+}
+
+// Whether the cells split over two groups along their rows or columns.
+fn two_groups(g: &Candidates, cells: &[(usize, usize); 4]) -> bool {
+    let [a, b, c, d] = cells.map(|(r, c)| g.group_of(r, c));
+    (a == b && c == d && a != c) || (a == c && b == d && a != b)
+}
+
+fn rectangles(g: &Candidates) -> Vec<Rectangle> {
+    let size = g.size();
+    let mut res = vec![];
+    for r1 in 0..size {
+        for r2 in r1 + 1..size {
+            for c1 in 0..size {
+                for c2 in c1 + 1..size {
+                    let cells = [(r1, c1), (r1, c2), (r2, c1), (r2, c2)];
+                    if !two_groups(g, &cells) {
+                        continue;
+                    }
+                    if cells.iter().any(|&(r, c)| g.value(r, c).is_some()) {
+                        continue;
+                    }
+                    let common = cells.iter().fold(!0, |m, &(r, c)| m & g.mask(r, c));
+                    let digits: Vec<u8> = mask_digits(common).collect();
+                    for pair in combinations(digits.len(), 2) {
+                        let pair = bit(digits[pair[0]]) | bit(digits[pair[1]]);
+                        let (floor, roof) = cells.iter().partition(|&&(r, c)| g.mask(r, c) == pair);
+                        res.push(Rectangle {
+                            cells,
+                            pair,
+                            floor,
+                            roof,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    res
+}
+
+fn extras(g: &Candidates, ur: &Rectangle) -> u32 {
+    ur.roof
+        .iter()
+        .fold(0, |m, &(r, c)| m | (g.mask(r, c) & !ur.pair))
+}
+
+// Units holding every given cell.
+fn shared_units(g: &Candidates, cells: &[(usize, usize)]) -> Vec<Unit> {
+    let (r, c) = cells[0];
+    let mut res = vec![];
+    if cells.iter().all(|&(r2, _)| r2 == r) {
+        res.push(Unit::Row(r));
+    }
+    if cells.iter().all(|&(_, c2)| c2 == c) {
+        res.push(Unit::Col(c));
+    }
+    if cells
+        .iter()
+        .all(|&(r2, c2)| g.group_of(r2, c2) == g.group_of(r, c))
+    {
+        res.push(Unit::Group(g.group_of(r, c)));
+    }
+    res
+}
+
+// Whether `d` sits only on rectangle cells along `unit`.
+fn confined(g: &Candidates, ur: &Rectangle, unit: &Unit, d: u8) -> bool {
+    spots(g, unit, d).iter().all(|cell| ur.cells.contains(cell))
+}
+
+fn step(
+    technique: Technique,
+    ur: &Rectangle,
+    what: String,
+    eliminations: Vec<(usize, usize, u8)>,
+) -> Option<Step> {
+    if eliminations.is_empty() {
+        return None;
+    }
+    let description = format!(
+        "{}: {} on {} {}, which {}",
+        technique,
+        digit_list(ur.pair),
+        cell_list(&ur.cells),
+        what,
+        describe_eliminations(&eliminations)
+    );
+    Some(Step {
+        technique,
+        cells: ur.cells.to_vec(),
+        chain_length: 0,
+        placements: vec![],
+        eliminations,
+        description,
+    })
+}
+
+fn type_1(_: &Candidates, ur: &Rectangle) -> Option<Step> {
+    if ur.roof.len() != 1 {
+        return None;
+    }
+    let (r, c) = ur.roof[0];
+    let eliminations = mask_digits(ur.pair).map(|d| (r, c, d)).collect();
+    let what = format!("would be deadly without the extras in {}", cell_name(r, c));
+    step(Technique::UniqueRectangle1, ur, what, eliminations)
+}
+
+// Type 2 and type 5: all roof cells carry the same single extra, so one of
+// them is that digit.
+fn same_extra(g: &Candidates, ur: &Rectangle, technique: Technique) -> Option<Step> {
+    let extra = extras(g, ur);
+    if extra.count_ones() != 1
+        || ur
+            .roof
+            .iter()
+            .any(|&(r, c)| g.mask(r, c) != ur.pair | extra)
+    {
+        return None;
+    }
+    let x = mask_digits(extra).next().unwrap();
+    let eliminations = seen_by_all(g, &ur.roof, x);
+    let what = format!("forces {} into {}", x, cell_list(&ur.roof));
+    step(technique, ur, what, eliminations)
+}
+
+fn type_2(g: &Candidates, ur: &Rectangle) -> Option<Step> {
+    if ur.roof.len() != 2 || shared_units(g, &ur.roof).is_empty() {
+        return None;
+    }
+    same_extra(g, ur, Technique::UniqueRectangle2)
+}
+
+// The roof's extras act as one cell forming a naked subset in a unit
+// shared by the roof.
+fn type_3(g: &Candidates, ur: &Rectangle) -> Option<Step> {
+    if ur.roof.len() != 2 {
+        return None;
+    }
+    let extra = extras(g, ur);
+    if extra.count_ones() < 2 {
+        return None;
+    }
+    for unit in shared_units(g, &ur.roof) {
+        let others: Vec<(usize, usize)> = g
+            .cells_of(unit)
+            .into_iter()
+            .filter(|&(r, c)| g.value(r, c).is_none() && !ur.roof.contains(&(r, c)))
+            .collect();
+        for k in 1..=3 {
+            for combo in combinations(others.len(), k) {
+                let subset: Vec<(usize, usize)> = combo.iter().map(|&i| others[i]).collect();
+                let mask = subset.iter().fold(extra, |m, &(r, c)| m | g.mask(r, c));
+                if mask.count_ones() as usize != k + 1 {
+                    continue;
+                }
+                let eliminations: Vec<(usize, usize, u8)> = others
+                    .iter()
+                    .filter(|cell| !subset.contains(cell))
+                    .flat_map(|&(r, c)| mask_digits(g.mask(r, c) & mask).map(move |d| (r, c, d)))
+                    .collect();
+                let what = format!(
+                    "leaves {} for the roof, a subset with {} in {}",
+                    digit_list(extra),
+                    cell_list(&subset),
+                    unit
+                );
+                if let Some(step) = step(Technique::UniqueRectangle3, ur, what, eliminations) {
+                    return Some(step);
+                }
+            }
+        }
+    }
+    None
+}
+
+// One of the pair is locked into the roof, so the other can't go there.
+fn type_4(g: &Candidates, ur: &Rectangle) -> Option<Step> {
+    if ur.roof.len() != 2 {
+        return None;
+    }
+    for unit in shared_units(g, &ur.roof) {
+        for a in mask_digits(ur.pair) {
+            let b = mask_digits(ur.pair & !bit(a)).next().unwrap();
+            if spots(g, &unit, a).iter().all(|cell| ur.roof.contains(cell)) {
+                let eliminations = ur.roof.iter().map(|&(r, c)| (r, c, b)).collect();
+                let what = format!("has {} locked in {} by {}", a, cell_list(&ur.roof), unit);
+                return step(Technique::UniqueRectangle4, ur, what, eliminations);
+            }
+        }
+    }
+    None
+}
+
+fn type_5(g: &Candidates, ur: &Rectangle) -> Option<Step> {
+    if ur.roof.len() == 2 && !shared_units(g, &ur.roof).is_empty() || ur.roof.len() < 2 {
+        return None;
+    }
+    same_extra(g, ur, Technique::UniqueRectangle5)
+}
+
+// Diagonal floor with `a` locked into the rectangle along both rows or both
+// columns: `a` on the roof would complete the deadly pattern.
+fn type_6(g: &Candidates, ur: &Rectangle) -> Option<Step> {
+    if ur.roof.len() != 2 || !shared_units(g, &ur.roof).is_empty() {
+        return None;
+    }
+    let [(r1, c1), _, _, (r2, c2)] = ur.cells;
+    for a in mask_digits(ur.pair) {
+        let rows = [Unit::Row(r1), Unit::Row(r2)];
+        let cols = [Unit::Col(c1), Unit::Col(c2)];
+        for lines in [rows, cols] {
+            if lines.iter().all(|unit| confined(g, ur, unit, a)) {
+                let eliminations = ur.roof.iter().map(|&(r, c)| (r, c, a)).collect();
+                let what = format!("has {} locked into {} and {}", a, lines[0], lines[1]);
+                return step(Technique::UniqueRectangle6, ur, what, eliminations);
+            }
+        }
+    }
+    None
+}
+
+// From a floor cell, `a` locked into the rectangle along the row and column
+// of the opposite corner keeps `b` out of that corner.
+fn hidden(g: &Candidates, ur: &Rectangle) -> Option<Step> {
+    for &(fr, fc) in ur.floor.iter() {
+        let (r, c) = *ur.cells.iter().find(|&&(r, c)| r != fr && c != fc).unwrap();
+        if ur.floor.contains(&(r, c)) {
+            continue;
+        }
+        for a in mask_digits(ur.pair) {
+            let b = mask_digits(ur.pair & !bit(a)).next().unwrap();
+            if confined(g, ur, &Unit::Row(r), a) && confined(g, ur, &Unit::Col(c), a) {
+                let what = format!("has {} locked into row {} and column {}", a, r + 1, c + 1);
+                return step(Technique::HiddenUniqueRectangle, ur, what, vec![(r, c, b)]);
+            }
+        }
+    }
+    None
+}
+
+pub(super) fn find(g: &Candidates, technique: Technique) -> Option<Step> {
+    if technique == Technique::Bug1 {
+        return bug_1(g);
+    }
+    let check = match technique {
+        Technique::UniqueRectangle1 => type_1,
+        Technique::UniqueRectangle2 => type_2,
+        Technique::UniqueRectangle3 => type_3,
+        Technique::UniqueRectangle4 => type_4,
+        Technique::UniqueRectangle5 => type_5,
+        Technique::UniqueRectangle6 => type_6,
+        _ => hidden,
+    };
+    rectangles(g).iter().find_map(|ur| check(g, ur))
+}
+
+// Bivalue cells everywhere but one cell of three: the digit showing up
+// three times in its units must go there, the grid would have two
+// solutions otherwise.
+fn bug_1(g: &Candidates) -> Option<Step> {
+    let mut odd = None;
+    for (r, c) in open_cells(g) {
+        match g.count(r, c) {
+            2 => {}
+            3 if odd.is_none() => odd = Some((r, c)),
+            _ => return None,
+        }
+    }
+    let (r, c) = odd?;
+    let d = g.digits(r, c).into_iter().find(|&d| {
+        [Unit::Row(r), Unit::Col(c), Unit::Group(g.group_of(r, c))]
+            .iter()
+            .all(|unit| spots(g, unit, d).len() == 3)
+    })?;
+    // Without d in the odd cell every digit left must appear twice per unit.
+    let bug = g.units().iter().all(|unit| {
+        all_digits(g).all(|digit| {
+            let n = spots(g, unit, digit)
+                .iter()
+                .filter(|&&cell| cell != (r, c) || digit != d)
+                .count();
+            n == 0 || n == 2
+        })
+    });
+    if !bug {
+        return None;
+    }
+    Some(Step {
+        technique: Technique::Bug1,
+        cells: vec![(r, c)],
+        chain_length: 0,
+        placements: vec![(r, c, d)],
+        eliminations: vec![],
+        description: format!(
+            "BUG+1: every open cell but {} is bivalue, so it is {}",
+            cell_name(r, c),
+            d
+        ),
+    })
+}
@@ -0,0 +1,157 @@
+use super::{
+    cell_list, cell_name, describe_eliminations, digit_list, open_cells, seen_by_all, sees, spots,
+    Step, Technique,
+};
+use crate::grid::{bit, mask_digits, Candidates};
+
+// Pivot xy with pincers xz and yz: whichever the pivot is, one pincer is z.
+pub(super) fn xy_wing(g: &Candidates) -> Option<Step> {
+    let bivalue: Vec<(usize, usize)> = open_cells(g)
+        .into_iter()
+        .filter(|&(r, c)| g.count(r, c) == 2)
+        .collect();
+    for &pivot in bivalue.iter() {
+        let pm = g.mask(pivot.0, pivot.1);
+        let pincers: Vec<(usize, usize)> = bivalue
+            .iter()
+            .copied()
+            .filter(|&p| sees(g, pivot, p))
+            .filter(|&(r, c)| (g.mask(r, c) & pm).count_ones() == 1)
+            .collect();
+        for (i, &a) in pincers.iter().enumerate() {
+            for &b in pincers[i + 1..].iter() {
+                let (am, bm) = (g.mask(a.0, a.1), g.mask(b.0, b.1));
+                let z = am & bm & !pm;
+                if z == 0 || am & pm == bm & pm {
+                    continue;
+                }
+                let d = z.trailing_zeros() as u8 + 1;
+                let eliminations = seen_by_all(g, &[a, b], d);
+                if !eliminations.is_empty() {
+                    let description = format!(
+                        "XY-Wing: pivot {} ({}) with pincers {} ({}) and {} ({}), which {}",
+                        cell_name(pivot.0, pivot.1),
+                        digit_list(pm),
+                        cell_name(a.0, a.1),
+                        digit_list(am),
+                        cell_name(b.0, b.1),
+                        digit_list(bm),
+                        describe_eliminations(&eliminations)
+                    );
+                    return Some(Step {
+                        technique: Technique::XYWing,
+                        cells: vec![pivot, a, b],
+                        chain_length: 0,
+                        placements: vec![],
+                        eliminations,
+                        description,
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+// Like the XY-Wing, but the pivot holds z too, so eliminations must also
+// see the pivot.
+pub(super) fn xyz_wing(g: &Candidates) -> Option<Step> {
+    let cells = open_cells(g);
+    for &pivot in cells.iter().filter(|&&(r, c)| g.count(r, c) == 3) {
+        let pm = g.mask(pivot.0, pivot.1);
+        let pincers: Vec<(usize, usize)> = cells
+            .iter()
+            .copied()
+            .filter(|&p| sees(g, pivot, p))
+            .filter(|&(r, c)| g.count(r, c) == 2 && g.mask(r, c) & !pm == 0)
+            .collect();
+        for (i, &a) in pincers.iter().enumerate() {
+            for &b in pincers[i + 1..].iter() {
+                let (am, bm) = (g.mask(a.0, a.1), g.mask(b.0, b.1));
+                let z = am & bm;
+                if am == bm || z.count_ones() != 1 {
+                    continue;
+                }
+                let d = z.trailing_zeros() as u8 + 1;
+                let eliminations = seen_by_all(g, &[pivot, a, b], d);
+                if !eliminations.is_empty() {
+                    let description = format!(
+                        "XYZ-Wing: pivot {} ({}) with pincers {} ({}) and {} ({}), which {}",
+                        cell_name(pivot.0, pivot.1),
+                        digit_list(pm),
+                        cell_name(a.0, a.1),
+                        digit_list(am),
+                        cell_name(b.0, b.1),
+                        digit_list(bm),
+                        describe_eliminations(&eliminations)
+                    );
+                    return Some(Step {
+                        technique: Technique::XYZWing,
+                        cells: vec![pivot, a, b],
+                        chain_length: 0,
+                        placements: vec![],
+                        eliminations,
+                        description,
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+// Two xy cells joined by a strong link on x: one of them must be y.
+pub(super) fn w_wing(g: &Candidates) -> Option<Step> {
+    let bivalue: Vec<(usize, usize)> = open_cells(g)
+        .into_iter()
+        .filter(|&(r, c)| g.count(r, c) == 2)
+        .collect();
+    let units = g.units();
+    for (i, &a) in bivalue.iter().enumerate() {
+        let m = g.mask(a.0, a.1);
+        for &b in bivalue[i + 1..].iter() {
+            if g.mask(b.0, b.1) != m || sees(g, a, b) {
+                continue;
+            }
+            for x in mask_digits(m) {
+                let y = mask_digits(m & !bit(x)).next().unwrap();
+                let eliminations = seen_by_all(g, &[a, b], y);
+                if eliminations.is_empty() {
+                    continue;
+                }
+                for unit in units.iter() {
+                    let link = spots(g, unit, x);
+                    if link.len() != 2 || link.contains(&a) || link.contains(&b) {
+                        continue;
+                    }
+                    let (p, q) = if sees(g, a, link[0]) && sees(g, b, link[1]) {
+                        (link[0], link[1])
+                    } else if sees(g, a, link[1]) && sees(g, b, link[0]) {
+                        (link[1], link[0])
+                    } else {
+                        continue;
+                    };
+                    let description = format!(
+                        "W-Wing: {} and {} ({}) joined by the strong link on {} in {} at {}, which {}",
+                        cell_name(a.0, a.1),
+                        cell_name(b.0, b.1),
+                        digit_list(m),
+                        x,
+                        unit,
+                        cell_list(&[p, q]),
+                        describe_eliminations(&eliminations)
+                    );
+                    return Some(Step {
+                        technique: Technique::WWing,
+                        cells: vec![a, p, q, b],
+                        chain_length: 0,
+                        placements: vec![],
+                        eliminations,
+                        description,
+                    });
+                }
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,480 @@
+use crate::error::Error;
+use crate::grid::{tokens, Cell, Table};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+// A validity-preserving change of a grid: an optional transpose, then a
+// row and a column permutation that keep bands and stacks together, then
+// digit relabelling. Cell (r, c) of the result comes from cell
+// (rows[r], cols[c]) of the possibly transposed source.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Transform {
+    transpose: bool,
+    rows: [usize; 9],
+    cols: [usize; 9],
+    digits: [u8; 9],
+}
+
+const SAME: [usize; 9] = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::identity()
+    }
+}
+
+// Whether `p` permutes 0..9 moving bands (or stacks) as a whole.
+fn keeps_bands(p: &[usize; 9]) -> bool {
+    let mut seen = [false; 9];
+    for (i, &x) in p.iter().enumerate() {
+        if x >= 9 || seen[x] || x / 3 != p[i / 3 * 3] / 3 {
+            return false;
+        }
+        seen[x] = true;
+    }
+    true
+}
+
+fn swap_lines(p: &mut [usize; 9], a: usize, b: usize) {
+    assert!(
+        a / 3 == b / 3,
+        "lines {} and {} are in different bands",
+        a,
+        b
+    );
+    p.swap(a, b);
+}
+
+fn swap_bands(p: &mut [usize; 9], a: usize, b: usize) {
+    assert!(a < 3 && b < 3);
+    for i in 0..3 {
+        p.swap(3 * a + i, 3 * b + i);
+    }
+}
+
+fn invert(p: &[usize; 9]) -> [usize; 9] {
+    let mut res = [0; 9];
+    for (i, &x) in p.iter().enumerate() {
+        res[x] = i;
+    }
+    res
+}
+
+// Only 9x9 grids with 3x3 boxes have the symmetries above. Diagonal ones
+// keep just those that also keep the diagonals, see `keeps_diagonals`.
+fn check(t: &Table) -> Result<(), Error> {
+    let variant = if t.size() != 9 {
+        format!("{}x{}", t.size(), t.size())
+    } else if t.regions().is_some() {
+        "jigsaw".to_string()
+    } else {
+        return Ok(());
+    };
+    Err(Error::Unsupported { variant })
+}
+
+// Whether `p` maps lines mirrored about the middle one to mirrored lines.
+fn mirrored(p: &[usize; 9]) -> bool {
+    (0..9).all(|i| p[8 - i] == 8 - p[i])
+}
+
+impl Transform {
+    pub fn identity() -> Transform {
+        Transform {
+            transpose: false,
+            rows: SAME,
+            cols: SAME,
+            digits: [1, 2, 3, 4, 5, 6, 7, 8, 9],
+        }
+    }
+
+    pub fn transpose() -> Transform {
+        Transform {
+            transpose: true,
+            ..Transform::identity()
+        }
+    }
+
+    // A quarter turn clockwise.
+    pub fn rotate() -> Transform {
+        let mut cols = SAME;
+        cols.reverse();
+        Transform {
+            transpose: true,
+            cols,
+            ..Transform::identity()
+        }
+    }
+
+    // Digit d becomes digits[d - 1].
+    pub fn relabel(digits: [u8; 9]) -> Transform {
+        let mut sorted = digits;
+        sorted.sort_unstable();
+        assert!(
+            sorted == [1, 2, 3, 4, 5, 6, 7, 8, 9],
+            "not a permutation of 1..=9"
+        );
+        Transform {
+            digits,
+            ..Transform::identity()
+        }
+    }
+
+    // Rows and columns are 0-based and must share a band or stack.
+    pub fn swap_rows(a: usize, b: usize) -> Transform {
+        let mut res = Transform::identity();
+        swap_lines(&mut res.rows, a, b);
+        res
+    }
+
+    pub fn swap_cols(a: usize, b: usize) -> Transform {
+        let mut res = Transform::identity();
+        swap_lines(&mut res.cols, a, b);
+        res
+    }
+
+    pub fn swap_bands(a: usize, b: usize) -> Transform {
+        let mut res = Transform::identity();
+        swap_bands(&mut res.rows, a, b);
+        res
+    }
+
+    pub fn swap_stacks(a: usize, b: usize) -> Transform {
+        let mut res = Transform::identity();
+        swap_bands(&mut res.cols, a, b);
+        res
+    }
+
+    // Uniform over all the transforms.
+    pub fn random<R: Rng>(rng: &mut R) -> Transform {
+        let mut res = Transform {
+            transpose: rng.gen(),
+            ..Transform::identity()
+        };
+        for p in [&mut res.rows, &mut res.cols] {
+            let mut bands = [0, 1, 2];
+            bands.shuffle(rng);
+            for (i, band) in bands.iter().enumerate() {
+                let mut lines = [3 * band, 3 * band + 1, 3 * band + 2];
+                lines.shuffle(rng);
+                p[3 * i..3 * i + 3].copy_from_slice(&lines);
+            }
+        }
+        res.digits.shuffle(rng);
+        res
+    }
+
+    // Both diagonals land on diagonals: rows are mirrored about the middle
+    // one, and columns follow them or their mirror image. Transposes,
+    // rotations and flips all qualify.
+    pub fn keeps_diagonals(&self) -> bool {
+        let flipped = self.rows.map(|r| 8 - r);
+        mirrored(&self.rows) && (self.cols == self.rows || self.cols == flipped)
+    }
+
+    pub fn apply(&self, t: &Table) -> Result<Table, Error> {
+        check(t)?;
+        if t.diagonals() && !self.keeps_diagonals() {
+            let variant = "diagonal".to_string();
+            return Err(Error::Unsupported { variant });
+        }
+        let mut res = Table::default();
+        res.set_diagonals(t.diagonals());
+        for r in 0..9 {
+            for c in 0..9 {
+                let (sr, sc) = (self.rows[r], self.cols[c]);
+                let cell = if self.transpose {
+                    t.grid[sc][sr]
+                } else {
+                    t.grid[sr][sc]
+                };
+                res.grid[r][c] = match cell {
+                    Cell::Digit(d) => Cell::Digit(self.digits[d as usize - 1]),
+                    Cell::Empty => Cell::Empty,
+                };
+            }
+        }
+        Ok(res)
+    }
+
+    // `self` first, then `next`.
+    pub fn then(&self, next: &Transform) -> Transform {
+        let (rows, cols) = if next.transpose {
+            (self.cols, self.rows)
+        } else {
+            (self.rows, self.cols)
+        };
+        Transform {
+            transpose: self.transpose != next.transpose,
+            rows: next.rows.map(|r| rows[r]),
+            cols: next.cols.map(|c| cols[c]),
+            digits: self.digits.map(|d| next.digits[d as usize - 1]),
+        }
+    }
+
+    pub fn inverse(&self) -> Transform {
+        let (rows, cols) = if self.transpose {
+            (invert(&self.cols), invert(&self.rows))
+        } else {
+            (invert(&self.rows), invert(&self.cols))
+        };
+        let mut digits = [0; 9];
+        for (i, &d) in self.digits.iter().enumerate() {
+            digits[d as usize - 1] = i as u8 + 1;
+        }
+        Transform {
+            transpose: self.transpose,
+            rows,
+            cols,
+            digits,
+        }
+    }
+
+    // `t` or `n` for the transpose, then rows, columns and digit images,
+    // 1-based: "n 123456789 123456789 123456789".
+    pub fn to_line(&self) -> String {
+        let line = |p: &[usize; 9]| p.iter().map(|x| (x + 1).to_string()).collect::<String>();
+        let digits: String = self.digits.iter().map(|d| d.to_string()).collect();
+        format!(
+            "{} {} {} {}",
+            if self.transpose { 't' } else { 'n' },
+            line(&self.rows),
+            line(&self.cols),
+            digits
+        )
+    }
+
+    pub fn from_line(line: &str) -> Result<Transform, Error> {
+        let mut res = Transform::identity();
+        let mut parts = tokens(line);
+        let (column, token) = parts.next().ok_or(Error::parse(1, 1, ""))?;
+        res.transpose = match token {
+            "t" => true,
+            "n" => false,
+            _ => return Err(Error::parse(1, column, token)),
+        };
+        for i in 0..3 {
+            let end = line.chars().count() + 1;
+            let (column, token) = parts.next().ok_or(Error::parse(1, end, ""))?;
+            let values: Vec<usize> = token
+                .chars()
+                .filter_map(|ch| ch.to_digit(10))
+                .filter(|&x| x >= 1)
+                .map(|x| x as usize - 1)
+                .collect();
+            if values.len() != 9 || token.chars().count() != 9 {
+                return Err(Error::parse(1, column, token));
+            }
+            let mut p = SAME;
+            p.copy_from_slice(&values);
+            let valid = match i {
+                2 => {
+                    let mut sorted = p;
+                    sorted.sort_unstable();
+                    sorted == SAME
+                }
+                _ => keeps_bands(&p),
+            };
+            if !valid {
+                return Err(Error::parse(1, column, token));
+            }
+            match i {
+                0 => res.rows = p,
+                1 => res.cols = p,
+                _ => res.digits = p.map(|x| x as u8 + 1),
+            }
+        }
+        match parts.next() {
+            Some((column, token)) => Err(Error::parse(1, column, token)),
+            None => Ok(res),
+        }
+    }
+}
+
+// Every permutation of 0..9 keeping bands together, 6^4 of them.
+fn band_permutations() -> Vec<[usize; 9]> {
+    const ORDERS: [[usize; 3]; 6] = [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ];
+    let mut res = vec![];
+    for bands in ORDERS {
+        for a in ORDERS {
+            for b in ORDERS {
+                for c in ORDERS {
+                    let mut p = [0; 9];
+                    for (i, inner) in [a, b, c].iter().enumerate() {
+                        for j in 0..3 {
+                            p[3 * i + j] = 3 * bands[i] + inner[j];
+                        }
+                    }
+                    res.push(p);
+                }
+            }
+        }
+    }
+    res
+}
+
+// Search state for one source grid and column order. Digits are relabelled
+// by first appearance, which is the smallest labelling for given positions.
+struct Canonical<'a> {
+    src: [[u8; 9]; 9],
+    cols: [usize; 9],
+    rows: [usize; 9],
+    grid: [[u8; 9]; 9],
+    best: &'a mut Option<([[u8; 9]; 9], Transform)>,
+    transpose: bool,
+}
+
+impl Canonical<'_> {
+    fn search(&mut self, depth: usize, labels: [u8; 10], next: u8) {
+        if depth == 9 {
+            let better = match self.best {
+                Some((grid, _)) => self.grid < *grid,
+                None => true,
+            };
+            if better {
+                let mut digits = labels;
+                let mut next = next;
+                for d in digits.iter_mut().skip(1) {
+                    if *d == 0 {
+                        *d = next;
+                        next += 1;
+                    }
+                }
+                let mut transform = Transform {
+                    transpose: self.transpose,
+                    rows: self.rows,
+                    cols: self.cols,
+                    digits: [0; 9],
+                };
+                transform.digits.copy_from_slice(&digits[1..]);
+                *self.best = Some((self.grid, transform));
+            }
+            return;
+        }
+        let used = &self.rows[..depth];
+        let candidates: Vec<usize> = if depth.is_multiple_of(3) {
+            (0..9)
+                .filter(|r| r % 3 == 0 && !used.iter().any(|u| u / 3 == r / 3))
+                .flat_map(|r| r..r + 3)
+                .collect()
+        } else {
+            let band = self.rows[depth - 1] / 3;
+            (3 * band..3 * band + 3)
+                .filter(|r| !used.contains(r))
+                .collect()
+        };
+        for r in candidates {
+            let (mut labels, mut next) = (labels, next);
+            for c in 0..9 {
+                let v = self.src[r][self.cols[c]] as usize;
+                if v != 0 && labels[v] == 0 {
+                    labels[v] = next;
+                    next += 1;
+                }
+                self.grid[depth][c] = labels[v];
+            }
+            if let Some((best, _)) = self.best {
+                if self.grid[..=depth] > best[..=depth] {
+                    continue;
+                }
+            }
+            self.rows[depth] = r;
+            self.search(depth + 1, labels, next);
+        }
+    }
+}
+
+// Diagonal grids only have the 96 arrangements keeping the diagonals, few
+// enough to try them all with digits relabelled by first appearance.
+fn canonical_diagonal(t: &Table) -> Transform {
+    let mut best: Option<([[u8; 9]; 9], Transform)> = None;
+    for rows in band_permutations().into_iter().filter(mirrored) {
+        for cols in [rows, rows.map(|r| 8 - r)] {
+            for transpose in [false, true] {
+                let mut transform = Transform {
+                    transpose,
+                    rows,
+                    cols,
+                    ..Transform::identity()
+                };
+                let moved = transform.apply(t).expect("the diagonals are kept");
+                let (mut labels, mut next) = ([0; 10], 1);
+                let mut grid = [[0; 9]; 9];
+                for (r, row) in grid.iter_mut().enumerate() {
+                    for (c, v) in row.iter_mut().enumerate() {
+                        if let Cell::Digit(d) = moved.grid[r][c] {
+                            if labels[d as usize] == 0 {
+                                labels[d as usize] = next;
+                                next += 1;
+                            }
+                            *v = labels[d as usize];
+                        }
+                    }
+                }
+                if best.as_ref().is_some_and(|(b, _)| *b <= grid) {
+                    continue;
+                }
+                for d in labels.iter_mut().skip(1) {
+                    if *d == 0 {
+                        *d = next;
+                        next += 1;
+                    }
+                }
+                transform.digits.copy_from_slice(&labels[1..]);
+                best = Some((grid, transform));
+            }
+        }
+    }
+    best.expect("the identity is always a candidate").1
+}
+
+// The lexicographically smallest grid equivalent to `t`, empty cells
+// counting as 0, and the transform leading there from `t`.
+pub fn canonicalize(t: &Table) -> Result<(Table, Transform), Error> {
+    check(t)?;
+    if t.diagonals() {
+        let transform = canonical_diagonal(t);
+        return Ok((transform.apply(t)?, transform));
+    }
+    let mut best = None;
+    let cols_orders = band_permutations();
+    for transpose in [false, true] {
+        let mut src = [[0; 9]; 9];
+        for (r, row) in src.iter_mut().enumerate() {
+            for (c, v) in row.iter_mut().enumerate() {
+                let cell = if transpose {
+                    t.grid[c][r]
+                } else {
+                    t.grid[r][c]
+                };
+                if let Cell::Digit(d) = cell {
+                    *v = d;
+                }
+            }
+        }
+        for &cols in cols_orders.iter() {
+            let mut state = Canonical {
+                src,
+                cols,
+                rows: SAME,
+                grid: [[0; 9]; 9],
+                best: &mut best,
+                transpose,
+            };
+            state.search(0, [0; 10], 1);
+        }
+    }
+    let (_, transform) = best.expect("the identity is always a candidate");
+    Ok((transform.apply(t)?, transform))
+}
+
+pub fn are_equivalent(a: &Table, b: &Table) -> Result<bool, Error> {
+    Ok(canonicalize(a)?.0 == canonicalize(b)?.0)
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use sudoku_solver_ed::error::Error;
+    use sudoku_solver_ed::grid::{Cell, Table};
+    use sudoku_solver_ed::solver::solve_simulated_annealing_chain;
+
+    fn solved_table() -> Table {
+        Table::from_arr([
+            [4, 3, 5, 2, 6, 9, 7, 8, 1],
+            [6, 8, 2, 5, 7, 1, 4, 9, 3],
+            [1, 9, 7, 8, 3, 4, 5, 6, 2],
+            [8, 2, 6, 1, 9, 5, 3, 4, 7],
+            [3, 7, 4, 6, 8, 2, 9, 1, 5],
+            [9, 5, 1, 7, 4, 3, 6, 2, 8],
+            [5, 1, 9, 3, 2, 6, 8, 7, 4],
+            [2, 4, 8, 9, 5, 7, 1, 3, 6],
+            [7, 6, 3, 4, 1, 8, 2, 5, 9],
+        ])
+    }
+
+    #[test]
+    fn ensure_simulated_annealing_chain() {
+        let mut a = solved_table();
+        for i in 0..9 {
+            a.grid[i][i] = Cell::Empty;
+        }
+        let (res, iters) = solve_simulated_annealing_chain(a, &AtomicBool::new(false));
+        assert_eq!(res.unwrap(), solved_table());
+        assert!(iters > 0);
+    }
+
+    #[test]
+    fn cancelled_chain_stops() {
+        let mut a = solved_table();
+        for i in 0..9 {
+            a.grid[4][i] = Cell::Empty;
+        }
+        let (res, iters) = solve_simulated_annealing_chain(a, &AtomicBool::new(true));
+        assert_eq!(res, Err(Error::Cancelled));
+        assert_eq!(iters, 0);
+    }
+}
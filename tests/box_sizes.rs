@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use sudoku_solver_ed::error::Error;
+    use sudoku_solver_ed::grid::{Cell, Shape, Table};
+    use sudoku_solver_ed::solver::dlx::solve_dlx;
+    use sudoku_solver_ed::solver::logic::solve_logic;
+    use sudoku_solver_ed::solver::{
+        count_solutions, solve_dfs_single, solve_propagating_dfs, solve_simulated_annealing_single,
+    };
+
+    // A complete grid: every row is the one above shifted by a box width,
+    // and by one more cell at the start of every band.
+    fn pattern(box_size: usize) -> Table {
+        let mut t = Table::with_box_size(box_size);
+        let size = t.size();
+        for r in 0..size {
+            for c in 0..size {
+                let d = ((r % box_size) * box_size + r / box_size + c) % size + 1;
+                t.grid[r][c] = Cell::Digit(d as u8);
+            }
+        }
+        t
+    }
+
+    #[test]
+    fn four_by_four_works() {
+        let a = Table::from_arr([[1, 0, 0, 0], [0, 0, 1, 0], [0, 1, 0, 0], [0, 0, 0, 1]]);
+        assert_eq!(a.size(), 4);
+        assert_eq!(a.shape(), Shape::square(2));
+        assert_eq!(a.to_line(), "1.....1..1.....1");
+        assert_eq!(Table::from_line(&a.to_line()), Ok(a.clone()));
+        let b = solve_dfs_single(a.clone()).unwrap();
+        assert!(b.solved());
+        assert!(solve_propagating_dfs(a.clone()).is_ok_and(|t| t.solved()));
+        assert!(solve_dlx(a.clone()).is_ok_and(|t| t.solved()));
+        assert!(solve_simulated_annealing_single(a.clone()).is_ok_and(|t| t.solved()));
+        assert!(count_solutions(&a, 2) > 1);
+        assert_eq!(count_solutions(&b, 2), 1);
+        assert!(pattern(2).solved());
+    }
+
+    #[test]
+    fn larger_boxes_work() {
+        for box_size in [4, 5] {
+            let b = pattern(box_size);
+            assert!(b.solved());
+            let mut a = b.clone();
+            let size = a.size();
+            for i in 0..size {
+                a.grid[i][i] = Cell::Empty;
+                a.grid[i][(3 * i) % size] = Cell::Empty;
+            }
+            assert_eq!(count_solutions(&a, 2), 1);
+            assert_eq!(solve_dlx(a.clone()).unwrap(), b);
+            assert_eq!(solve_propagating_dfs(a.clone()).unwrap(), b);
+            assert_eq!(solve_logic(&a).unwrap().table, b);
+        }
+    }
+
+    #[test]
+    fn letters_in_lines() {
+        let b = pattern(4);
+        let line = b.to_line();
+        assert!(line.starts_with("123456789ABCDEFG56789ABCDEFG1234"));
+        assert_eq!(Table::from_line(&line.to_lowercase()), Ok(b));
+        assert_eq!(
+            Table::from_line(&format!("A{}", &".".repeat(80))),
+            Err(Error::Parse {
+                line: 1,
+                column: 1,
+                token: "A".to_string()
+            })
+        );
+        assert_eq!(
+            Table::from_line(&"Q".repeat(625)).unwrap_err(),
+            Error::Parse {
+                line: 1,
+                column: 1,
+                token: "Q".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod tests {
+    use sudoku_solver_ed::grid::{mask_digits, Candidates, Cell, Table};
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn candidates_from_table_works() {
+        let t = Table::from_line(PUZZLE).unwrap();
+        let cand = Candidates::new(&t).unwrap();
+        assert_eq!(cand.value(0, 0), Some(5));
+        assert_eq!(cand.mask(0, 0), 0);
+        assert_eq!(cand.digits(0, 2), vec![1, 2, 4]);
+        assert_eq!(cand.count(4, 4), 1);
+        assert!(cand.can_place(4, 4, 5));
+        assert!(!cand.can_place(4, 4, 4));
+        assert_eq!(cand.to_table(), t);
+
+        let mut bad = Table::from_line(PUZZLE).unwrap();
+        bad.grid[0][8] = Cell::Digit(3);
+        assert!(Candidates::new(&bad).is_err());
+    }
+
+    #[test]
+    fn place_and_remove_work() {
+        let t = Table::from_line(PUZZLE).unwrap();
+        let mut cand = Candidates::new(&t).unwrap();
+        let before = cand.clone();
+        cand.place(0, 2, 4);
+        assert_eq!(cand.value(0, 2), Some(4));
+        assert_eq!(cand.digits(0, 3), vec![2, 6]);
+        assert_eq!(cand.digits(2, 0), vec![1, 2]);
+        assert!(!cand.can_place(7, 2, 4));
+        cand.remove(0, 2);
+        assert_eq!(cand, before);
+
+        assert!(cand.eliminate(0, 2, 1));
+        assert!(!cand.eliminate(0, 2, 1));
+        assert_eq!(mask_digits(cand.mask(0, 2)).collect::<Vec<_>>(), vec![2, 4]);
+    }
+}
@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use sudoku_solver_ed::error::Error;
+    use sudoku_solver_ed::grid::{Cell, Regions, Table};
+    use sudoku_solver_ed::transform::{are_equivalent, canonicalize, Transform};
+
+    #[test]
+    fn canonicalize_works() {
+        let a = Table::from_line(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        let (c, t) = canonicalize(&a).unwrap();
+        assert_eq!(t.apply(&a).unwrap(), c);
+        assert_eq!(canonicalize(&c).unwrap().0, c);
+        assert!(c.to_line().starts_with("........1"));
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..3 {
+            let b = Transform::random(&mut rng).apply(&a).unwrap();
+            assert_eq!(canonicalize(&b).unwrap().0, c);
+        }
+    }
+
+    #[test]
+    fn are_equivalent_works() {
+        let a = Table::from_line(
+            "435269781682571493197834562826195347374682915951743628519326874248957136763418259",
+        )
+        .unwrap();
+        let mut rng = StdRng::seed_from_u64(4);
+        let b = Transform::random(&mut rng).apply(&a).unwrap();
+        assert_eq!(are_equivalent(&a, &b), Ok(true));
+        let mut c = a.clone();
+        c.grid[0][0] = Cell::Empty;
+        assert_eq!(are_equivalent(&a, &c), Ok(false));
+    }
+
+    #[test]
+    fn other_grids_are_unsupported() {
+        let unsupported = |variant: &str| Error::Unsupported {
+            variant: variant.to_string(),
+        };
+        let small = Table::with_box_size(2);
+        assert_eq!(
+            Transform::transpose().apply(&small).unwrap_err(),
+            unsupported("4x4")
+        );
+        assert_eq!(
+            are_equivalent(&small, &small).unwrap_err(),
+            unsupported("4x4")
+        );
+        let mut jigsaw = Table::default();
+        let layout = "111222333".repeat(3) + &"444555666".repeat(3) + &"777888999".repeat(3);
+        jigsaw.set_regions(Some(Regions::from_text(&layout).unwrap()));
+        assert_eq!(canonicalize(&jigsaw).unwrap_err(), unsupported("jigsaw"));
+    }
+}
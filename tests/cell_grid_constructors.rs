@@ -1,3 +1,5 @@
+#![allow(clippy::clone_on_copy)]
+
 #[cfg(test)]
 mod tests {
     use sudoku_solver_ed::grid::{Cell, Table};
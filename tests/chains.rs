@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use sudoku_solver_ed::grid::{Candidates, Cell, Table};
+    use sudoku_solver_ed::solver::logic::{solve_logic, LogicSolver, Technique};
+    use sudoku_solver_ed::solver::solve_dlx;
+
+    #[test]
+    fn x_chain_works() {
+        let mut g = Candidates::new(&Table::default()).unwrap();
+        for (row, cols) in [(0, [1, 6]), (4, [1, 7])] {
+            for c in (0..9).filter(|c| !cols.contains(c)) {
+                g.eliminate(row, c, 1);
+            }
+        }
+        let step = LogicSolver::with_techniques(&[Technique::XChain])
+            .next_step(&g)
+            .unwrap();
+        assert_eq!(step.technique, Technique::XChain);
+        assert_eq!(
+            step.eliminations,
+            vec![(1, 7, 1), (2, 7, 1), (3, 6, 1), (5, 6, 1)]
+        );
+        assert_eq!(
+            step.description,
+            "X-Chain: (1)r1c7=(1)r1c2-(1)r5c2=(1)r5c8, which removes 1 from r2c8, r3c8, r4c7, r6c7"
+        );
+    }
+
+    #[test]
+    fn xy_chain_works() {
+        let mut g = Candidates::new(&Table::default()).unwrap();
+        for (r, c, keep) in [(0, 0, [1, 2]), (0, 5, [1, 3]), (1, 2, [2, 3])] {
+            for d in (1..=9).filter(|d| !keep.contains(d)) {
+                g.eliminate(r, c, d);
+            }
+        }
+        let step = LogicSolver::with_techniques(&[Technique::XYChain])
+            .next_step(&g)
+            .unwrap();
+        assert_eq!(step.cells, vec![(0, 5), (0, 0), (1, 2)]);
+        assert_eq!(step.chain_length, 6);
+        assert_eq!(
+            step.description,
+            "XY-Chain: (3=1)r1c6-(1=2)r1c1-(2=3)r2c3, which removes 3 from r1c2, r1c3, r2c4, r2c5, r2c6"
+        );
+    }
+
+    #[test]
+    fn chains_solve_hard_puzzle() {
+        let a = Table::from_line(
+            ".524.........7.1..............8.2...3.....6...9.5.....1.6.3...........897........",
+        )
+        .unwrap();
+        let solution = solve_dlx(a.clone()).unwrap();
+        let w = solve_logic(&a).unwrap();
+        assert!(w.techniques().contains(&Technique::Aic));
+        assert!(w.solved());
+        assert_eq!(w.table, solution);
+        for step in w.steps.iter() {
+            for &(r, c, d) in step.eliminations.iter() {
+                assert_ne!(solution.grid[r][c], Cell::Digit(d));
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests {
+    use sudoku_solver_ed::grid::{Cell, Conflict, Table, Unit};
+
+    #[test]
+    fn conflicts_of_valid_table_are_empty() {
+        let a = Table::from_line(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        assert!(a.conflicts().is_empty());
+        assert!(Table::default().conflicts().is_empty());
+    }
+
+    #[test]
+    fn conflicts_works() {
+        let mut a: Table = Default::default();
+        a.grid[0][0] = Cell::Digit(4);
+        a.grid[0][5] = Cell::Digit(4);
+        a.grid[7][0] = Cell::Digit(4);
+        a.grid[4][4] = Cell::Digit(9);
+        a.grid[5][3] = Cell::Digit(9);
+        a.grid[8][4] = Cell::Digit(9);
+        assert_eq!(
+            a.conflicts(),
+            vec![
+                Conflict {
+                    unit: Unit::Row(0),
+                    digit: 4,
+                    cells: vec![(0, 0), (0, 5)],
+                },
+                Conflict {
+                    unit: Unit::Col(0),
+                    digit: 4,
+                    cells: vec![(0, 0), (7, 0)],
+                },
+                Conflict {
+                    unit: Unit::Col(4),
+                    digit: 9,
+                    cells: vec![(4, 4), (8, 4)],
+                },
+                Conflict {
+                    unit: Unit::Group(4),
+                    digit: 9,
+                    cells: vec![(4, 4), (5, 3)],
+                },
+            ]
+        );
+        assert_eq!(
+            format!("{}", a.conflicts()[3]),
+            "9 repeated in group 5 at r5c5, r6c4"
+        );
+
+        let b = Table::new([[Cell::Digit(3); 9]; 9]);
+        let conflicts = b.conflicts();
+        assert_eq!(conflicts.len(), 27);
+        assert!(conflicts.iter().all(|c| c.digit == 3 && c.cells.len() == 9));
+    }
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use sudoku_solver_ed::grid::{Cell, Table};
+    use sudoku_solver_ed::solver::{count_solutions, is_unique};
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn count_solutions_works() {
+        let a = Table::from_line(PUZZLE).unwrap();
+        assert_eq!(count_solutions(&a, 10), 1);
+        assert_eq!(count_solutions(&a, 0), 0);
+
+        // Two digits that can be swapped in a rectangle over two groups.
+        let mut b = Table::from_line(
+            "435269781682571493197834562826195347374682915951743628519326874248957136763418259",
+        )
+        .unwrap();
+        b.grid[0][2] = Cell::Empty;
+        b.grid[0][3] = Cell::Empty;
+        b.grid[1][2] = Cell::Empty;
+        assert_eq!(count_solutions(&b, 10), 1);
+        b.grid[1][3] = Cell::Empty;
+        assert_eq!(count_solutions(&b, 10), 2);
+        assert_eq!(count_solutions(&b, 1), 1);
+
+        let empty = Table::default();
+        assert_eq!(count_solutions(&empty, 5), 5);
+
+        let mut bad = Table::from_line(PUZZLE).unwrap();
+        bad.grid[0][2] = Cell::Digit(5);
+        assert_eq!(count_solutions(&bad, 10), 0);
+    }
+
+    #[test]
+    fn is_unique_works() {
+        let mut a = Table::from_line(PUZZLE).unwrap();
+        assert!(is_unique(&a));
+        a.grid[0][0] = Cell::Empty;
+        a.grid[0][1] = Cell::Empty;
+        a.grid[1][0] = Cell::Empty;
+        a.grid[4][0] = Cell::Empty;
+        assert!(!is_unique(&a));
+        assert!(!is_unique(&Table::default()));
+    }
+}
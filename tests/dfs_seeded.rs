@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use sudoku_solver_ed::error::Error;
+    use sudoku_solver_ed::grid::Table;
+    use sudoku_solver_ed::solver::solve_randomized_dfs_seeded;
+
+    const PUZZLE: &str =
+        "..5.69.8.682.7..9.19.83456.82619.3.73.468.9159.1..3.28..9.26.7...8.57136.6..18259";
+    const SOLUTION: &str =
+        "435269781682571493197834562826195347374682915951743628519326874248957136763418259";
+
+    #[test]
+    fn seeded_dfs_is_reproducible() {
+        let cancel = AtomicBool::new(false);
+        let a = Table::from_line(PUZZLE).unwrap();
+        let (res, nodes) = solve_randomized_dfs_seeded(a.clone(), 7, &cancel);
+        assert_eq!(res.unwrap(), Table::from_line(SOLUTION).unwrap());
+        assert!(nodes >= 30);
+        let (_, again) = solve_randomized_dfs_seeded(a, 7, &cancel);
+        assert_eq!(nodes, again);
+    }
+
+    #[test]
+    fn cancelled_dfs_stops() {
+        let a = Table::from_line(PUZZLE).unwrap();
+        let (res, nodes) = solve_randomized_dfs_seeded(a, 7, &AtomicBool::new(true));
+        assert_eq!(res, Err(Error::Cancelled));
+        assert_eq!(nodes, 0);
+
+        let b = Table::from_line(
+            "..........82561493.97834562.26195347.74682915.51743628.19326874.48957136.63418259",
+        )
+        .unwrap();
+        let (res, _) = solve_randomized_dfs_seeded(b, 7, &AtomicBool::new(false));
+        assert_eq!(res, Err(Error::NoSolution));
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use sudoku_solver_ed::error::Error;
+    use sudoku_solver_ed::grid::{Candidates, Cell, Table, Unit};
+    use sudoku_solver_ed::solver::logic::solve_logic;
+    use sudoku_solver_ed::solver::{
+        count_solutions, solve_dfs_single, solve_dlx, solve_propagating_dfs,
+        solve_simulated_annealing_single,
+    };
+    use sudoku_solver_ed::transform::{are_equivalent, canonicalize, Transform};
+
+    const PUZZLE: &str =
+        ".....8.9......4....7.3......6......5......8.3....6.2....8.51.....5....3.6......2.";
+
+    const SOLUTION: &str =
+        "234578691819624357576319482162483975457192863983765214398251746725846139641937528";
+
+    fn diagonal(line: &str) -> Table {
+        let mut t = Table::from_line(line).unwrap();
+        t.set_diagonals(true);
+        t
+    }
+
+    #[test]
+    fn diagonal_checks_work() {
+        let mut a = Table::from_line(
+            "435269781682571493197834562826195347374682915951743628519326874248957136763418259",
+        )
+        .unwrap();
+        assert!(a.solved());
+        a.set_diagonals(true);
+        assert!(!a.solved());
+        assert!(!a.diagonal_is_ok(0));
+        assert_eq!(a.units().len(), 29);
+        let conflicts = a.conflicts();
+        assert_eq!(conflicts[0].unit, Unit::Diagonal(0));
+        assert_eq!(
+            format!("{}", conflicts[0]),
+            "3 repeated in main diagonal at r6c6, r8c8"
+        );
+        assert!(diagonal(SOLUTION).solved());
+    }
+
+    #[test]
+    fn candidates_see_diagonals() {
+        let mut cand = Candidates::new(&diagonal(&".".repeat(81))).unwrap();
+        cand.place(0, 0, 5);
+        assert!(!cand.can_place(4, 4, 5));
+        assert!(cand.can_place(4, 5, 5));
+        cand.place(2, 6, 7);
+        assert!(!cand.can_place(6, 2, 7));
+        assert!(!cand.can_place(4, 4, 7));
+        cand.remove(0, 0);
+        assert!(cand.can_place(4, 4, 5));
+        assert!(!cand.can_place(4, 4, 7));
+    }
+
+    #[test]
+    fn diagonal_solvers_work() {
+        let a = diagonal(PUZZLE);
+        let b = diagonal(SOLUTION);
+        assert_eq!(count_solutions(&a, 2), 1);
+        assert_eq!(count_solutions(&Table::from_line(PUZZLE).unwrap(), 2), 2);
+        assert_eq!(solve_propagating_dfs(a.clone()).unwrap(), b);
+        assert_eq!(solve_dlx(a.clone()).unwrap(), b);
+        assert_eq!(
+            solve_logic(&a).unwrap_err(),
+            Error::Unsupported {
+                variant: "diagonal".to_string()
+            }
+        );
+        let mut c = b.clone();
+        for i in 0..9 {
+            c.grid[i][i] = Cell::Empty;
+            c.grid[i][8 - i] = Cell::Empty;
+        }
+        assert_eq!(solve_dfs_single(c.clone()).unwrap(), b);
+        assert_eq!(solve_simulated_annealing_single(c), Ok(b));
+    }
+
+    #[test]
+    fn diagonal_transforms_work() {
+        let b = diagonal(SOLUTION);
+        let r = Transform::rotate().apply(&b).unwrap();
+        assert!(r.diagonals() && r.solved());
+        assert_eq!(
+            Transform::swap_rows(0, 1).apply(&b).unwrap_err(),
+            Error::Unsupported {
+                variant: "diagonal".to_string()
+            }
+        );
+        let x = Transform::swap_bands(0, 2)
+            .then(&Transform::swap_stacks(0, 2))
+            .then(&Transform::transpose())
+            .then(&Transform::relabel([9, 8, 7, 6, 5, 4, 3, 2, 1]));
+        assert!(x.keeps_diagonals());
+        assert!(!Transform::swap_bands(0, 2).keeps_diagonals());
+
+        let a = diagonal(PUZZLE);
+        let moved = x.apply(&a).unwrap();
+        assert_eq!(count_solutions(&moved, 2), 1);
+        let (c, t) = canonicalize(&a).unwrap();
+        assert_eq!(t.apply(&a).unwrap(), c);
+        assert_eq!(canonicalize(&moved).unwrap().0, c);
+        assert_eq!(are_equivalent(&a, &moved), Ok(true));
+        assert_eq!(
+            are_equivalent(&a, &Table::from_line(PUZZLE).unwrap()),
+            Ok(false)
+        );
+    }
+}
@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod tests {
+    use sudoku_solver_ed::error::Error;
+    use sudoku_solver_ed::grid::{Cell, Table};
+    use sudoku_solver_ed::solver::dlx::solve_dlx;
+    use sudoku_solver_ed::solver::solve_propagating_dfs;
+
+    #[test]
+    fn ensure_dlx() {
+        let mut a = Table::from_arr([
+            [4, 3, 5, 2, 6, 9, 7, 8, 1],
+            [6, 8, 2, 5, 7, 1, 4, 9, 3],
+            [1, 9, 7, 8, 3, 4, 5, 6, 2],
+            [8, 2, 6, 1, 9, 5, 3, 4, 7],
+            [3, 7, 4, 6, 8, 2, 9, 1, 5],
+            [9, 5, 1, 7, 4, 3, 6, 2, 8],
+            [5, 1, 9, 3, 2, 6, 8, 7, 4],
+            [2, 4, 8, 9, 5, 7, 1, 3, 6],
+            [7, 6, 3, 4, 1, 8, 2, 5, 9],
+        ]);
+        let b = a.clone();
+        for i in 0..9 {
+            a.grid[i][8 - i] = Cell::Empty;
+            a.grid[4][i] = Cell::Empty;
+        }
+        assert_eq!(solve_dlx(a).unwrap(), b);
+        assert_eq!(solve_dlx(b.clone()).unwrap(), b);
+    }
+
+    #[test]
+    fn dlx_solves_hard_puzzle() {
+        let a = Table::from_line(
+            "52...6.........7.13...........4..8..6......5...........418.........3..2...87.....",
+        )
+        .unwrap();
+        let res = solve_dlx(a.clone()).unwrap();
+        assert!(res.solved());
+        assert_eq!(res, solve_propagating_dfs(a).unwrap());
+    }
+
+    #[test]
+    fn dlx_detects_no_solution() {
+        let a = Table::from_line(
+            "..........82561493.97834562.26195347.74682915.51743628.19326874.48957136.63418259",
+        )
+        .unwrap();
+        assert_eq!(solve_dlx(a), Err(Error::NoSolution));
+        let mut b = Table::default();
+        b.grid[0][0] = Cell::Digit(1);
+        b.grid[8][0] = Cell::Digit(1);
+        assert!(solve_dlx(b).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use sudoku_solver_ed::app::puzzles_from_lines;
+    use sudoku_solver_ed::error::Error;
+    use sudoku_solver_ed::grid::{Candidates, Cell, Table};
+    use sudoku_solver_ed::solver::{
+        solve_dfs_single, solve_propagating_dfs, solve_simulated_annealing_chain,
+    };
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn parse_errors_point_at_token() {
+        assert_eq!(
+            Table::from_line(&PUZZLE.replace('7', "x")),
+            Err(Error::Parse {
+                line: 1,
+                column: 5,
+                token: "x".to_string()
+            })
+        );
+        assert_eq!(
+            Table::from_line(&PUZZLE[..80]),
+            Err(Error::Parse {
+                line: 1,
+                column: 81,
+                token: String::new()
+            })
+        );
+        let puzzles = puzzles_from_lines(&format!("{}\n\n{}9\n", PUZZLE, PUZZLE));
+        assert_eq!(
+            puzzles[1].1,
+            Err(Error::Parse {
+                line: 3,
+                column: 82,
+                token: "9".to_string()
+            })
+        );
+        assert_eq!(
+            format!("{}", puzzles[1].1.clone().unwrap_err()),
+            "line 3, column 82: unexpected `9`"
+        );
+    }
+
+    #[test]
+    fn solver_errors_are_distinct() {
+        let mut bad = Table::from_line(PUZZLE).unwrap();
+        bad.grid[0][2] = Cell::Digit(5);
+        let contradiction = Error::Contradiction {
+            conflicts: bad.conflicts(),
+        };
+        assert_eq!(
+            Candidates::new(&bad).map(|_| ()),
+            Err(contradiction.clone())
+        );
+        assert_eq!(
+            solve_dfs_single(bad.clone()).map(|_| ()),
+            Err(contradiction.clone())
+        );
+        assert_eq!(
+            format!("{}", contradiction),
+            "givens contradict each other: 5 repeated in row 1 at r1c1, r1c3; \
+             5 repeated in group 1 at r1c1, r1c3"
+        );
+        assert!(matches!(
+            solve_simulated_annealing_chain(bad, &AtomicBool::new(false)).0,
+            Err(Error::Contradiction { .. })
+        ));
+
+        let unsolvable = Table::from_line(
+            "..........82561493.97834562.26195347.74682915.51743628.19326874.48957136.63418259",
+        )
+        .unwrap();
+        assert_eq!(
+            solve_propagating_dfs(unsolvable.clone()),
+            Err(Error::NoSolution)
+        );
+        assert!(matches!(
+            solve_simulated_annealing_chain(unsolvable, &AtomicBool::new(false)).0,
+            Err(Error::BudgetExhausted { iterations: 130000 })
+        ));
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    use sudoku_solver_ed::grid::{Candidates, Table};
+    use sudoku_solver_ed::solver::logic::{LogicSolver, Technique};
+
+    // Leaves candidate `d` in `row` only at `cols`.
+    fn keep_in_row(g: &mut Candidates, d: u8, row: usize, cols: &[usize]) {
+        for c in (0..9).filter(|c| !cols.contains(c)) {
+            g.eliminate(row, c, d);
+        }
+    }
+
+    #[test]
+    fn x_wing_and_finned_x_wing_work() {
+        let mut g = Candidates::new(&Table::default()).unwrap();
+        keep_in_row(&mut g, 1, 0, &[1, 6]);
+        keep_in_row(&mut g, 1, 4, &[1, 6]);
+        let step = LogicSolver::with_techniques(&[Technique::XWing])
+            .next_step(&g)
+            .unwrap();
+        assert_eq!(step.technique, Technique::XWing);
+        assert_eq!(step.cells, vec![(0, 1), (0, 6), (4, 1), (4, 6)]);
+        assert_eq!(step.eliminations.len(), 14);
+        assert!(step
+            .eliminations
+            .iter()
+            .all(|&(_, c, d)| d == 1 && (c == 1 || c == 6)));
+
+        g = Candidates::new(&Table::default()).unwrap();
+        keep_in_row(&mut g, 1, 0, &[1, 6]);
+        keep_in_row(&mut g, 1, 4, &[1, 6, 7]);
+        assert!(LogicSolver::with_techniques(&[Technique::XWing])
+            .next_step(&g)
+            .is_none());
+        let step = LogicSolver::with_techniques(&[Technique::FinnedXWing])
+            .next_step(&g)
+            .unwrap();
+        assert_eq!(step.eliminations, vec![(3, 6, 1), (5, 6, 1)]);
+        assert_eq!(
+            step.description,
+            "Finned X-Wing: 1 in rows 1, 5 is confined to columns 2, 7 with fins at r5c8, \
+             which removes 1 from r4c7, r6c7"
+        );
+    }
+
+    #[test]
+    fn xy_wing_works_and_can_be_disabled() {
+        let mut g = Candidates::new(&Table::default()).unwrap();
+        for (r, c, keep) in [(0, 0, [1, 2]), (0, 5, [1, 3]), (1, 2, [2, 3])] {
+            for d in (1..=9).filter(|d| !keep.contains(d)) {
+                g.eliminate(r, c, d);
+            }
+        }
+        let mut solver = LogicSolver::new();
+        let step = solver.next_step(&g).unwrap();
+        assert_eq!(step.technique, Technique::XYWing);
+        assert_eq!(step.cells, vec![(0, 0), (0, 5), (1, 2)]);
+        assert_eq!(
+            step.eliminations,
+            vec![(0, 1, 3), (0, 2, 3), (1, 3, 3), (1, 4, 3), (1, 5, 3)]
+        );
+
+        solver.disable(Technique::XYWing);
+        assert!(!solver.techniques().contains(&Technique::XYWing));
+        assert!(solver
+            .next_step(&g)
+            .is_none_or(|s| s.technique != Technique::XYWing));
+        solver.enable(Technique::XYWing);
+        assert_eq!(solver, LogicSolver::new());
+    }
+}
@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use sudoku_solver_ed::error::Error;
+    use sudoku_solver_ed::generator::{generate, Settings, Symmetry};
+    use sudoku_solver_ed::solver::logic::Technique;
+    use sudoku_solver_ed::solver::{is_minimal, is_unique, solve_dlx};
+
+    #[test]
+    fn generate_works() {
+        for seed in 0..5 {
+            let p = generate(&Settings {
+                clues: 0,
+                seed,
+                ..Settings::default()
+            })
+            .unwrap();
+            assert!(p.solution.solved());
+            assert!(is_unique(&p.table));
+            assert_eq!(solve_dlx(p.table.clone()), Ok(p.solution.clone()));
+            assert!(p.clues() < 36);
+        }
+    }
+
+    #[test]
+    fn generate_is_reproducible_and_stops_at_clues() {
+        let settings = Settings {
+            clues: 30,
+            seed: 42,
+            ..Settings::default()
+        };
+        let p = generate(&settings).unwrap();
+        assert_eq!(p, generate(&settings).unwrap());
+        assert_eq!(p.clues(), 30);
+        assert!(is_unique(&p.table));
+        assert_ne!(
+            p,
+            generate(&Settings {
+                seed: 43,
+                ..settings
+            })
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn generate_keeps_symmetry() {
+        assert_eq!(Symmetry::Rotational90.orbit((0, 1)).len(), 4);
+        assert_eq!(Symmetry::Dihedral.orbit((4, 4)), vec![(4, 4)]);
+        assert_eq!(Symmetry::Dihedral.orbits().len(), 15);
+        for (i, &symmetry) in Symmetry::ALL.iter().enumerate() {
+            let p = generate(&Settings {
+                clues: 0,
+                seed: i as u64,
+                symmetry,
+                ..Settings::default()
+            })
+            .unwrap();
+            assert!(symmetry.matches(&p.table), "{}", symmetry.name());
+            assert!(is_unique(&p.table));
+        }
+    }
+
+    #[test]
+    fn generate_meets_rating_and_budget() {
+        let p = generate(&Settings {
+            seed: 5,
+            rating: Some(3.0..=4.5),
+            ..Settings::default()
+        })
+        .unwrap();
+        let score = p.rating.unwrap().score;
+        assert!((3.0..=4.5).contains(&score));
+
+        let p = generate(&Settings {
+            seed: 5,
+            hardest: Some(Technique::Pointing),
+            ..Settings::default()
+        })
+        .unwrap();
+        assert_eq!(p.rating.unwrap().hardest, Some(Technique::Pointing));
+
+        let res = generate(&Settings {
+            seed: 5,
+            rating: Some(12.0..=13.0),
+            time_budget: Some(Duration::ZERO),
+            ..Settings::default()
+        });
+        assert_eq!(res, Err(Error::NoMatch { rejected: 1 }));
+
+        let res = generate(&Settings {
+            seed: 5,
+            rating: Some(12.0..=13.0),
+            max_rejected: 3,
+            ..Settings::default()
+        });
+        assert_eq!(res, Err(Error::NoMatch { rejected: 3 }));
+    }
+
+    #[test]
+    fn generate_minimal_works() {
+        for seed in 0..4 {
+            let mut p = generate(&Settings {
+                clues: 30,
+                seed,
+                minimal: true,
+                ..Settings::default()
+            })
+            .unwrap();
+            assert!(is_minimal(&p.table));
+            let (r, c) = p.table.empty_cells()[0];
+            p.table.grid[r][c] = p.solution.grid[r][c];
+            assert!(is_unique(&p.table));
+            assert!(!is_minimal(&p.table));
+        }
+        assert!(!is_minimal(&Default::default()));
+        let res = generate(&Settings {
+            symmetry: Symmetry::Rotational180,
+            minimal: true,
+            ..Settings::default()
+        });
+        assert!(matches!(res, Err(Error::InvalidSettings { .. })));
+    }
+}
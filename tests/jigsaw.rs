@@ -0,0 +1,174 @@
+#[cfg(test)]
+mod tests {
+    use sudoku_solver_ed::app::puzzles_from_lines_with_regions;
+    use sudoku_solver_ed::error::Error;
+    use sudoku_solver_ed::grid::{Candidates, Cell, Regions, Table, Unit};
+    use sudoku_solver_ed::rating::rate;
+    use sudoku_solver_ed::solver::logic::solve_logic;
+    use sudoku_solver_ed::solver::{
+        count_solutions, solve_dfs_single, solve_dlx, solve_propagating_dfs,
+        solve_simulated_annealing_single,
+    };
+
+    const LAYOUT: &str = "111122333
+111222333
+112222633
+444555663
+444555666
+444558666
+777588999
+777888999
+777888999
+";
+
+    const PUZZLE: &str =
+        "............6.2....6..1...2.5......4....2.3.1..6...7......3....4.1......3..8.1.5.";
+
+    const SOLUTION: &str =
+        "512374689748692513963518472257163894894725361136489725625937148481256937379841256";
+
+    fn jigsaw(line: &str) -> Table {
+        let mut t = Table::from_line(line).unwrap();
+        t.set_regions(Some(Regions::from_text(LAYOUT).unwrap()));
+        t
+    }
+
+    #[test]
+    fn regions_text_works() {
+        let regions = Regions::from_text(LAYOUT).unwrap();
+        assert_eq!(regions.to_text(), LAYOUT);
+        assert_eq!(
+            Regions::from_text(&LAYOUT.replace('\n', "")),
+            Ok(regions.clone())
+        );
+        assert_eq!(regions.region_of(0, 3), 0);
+        assert_eq!(regions.cells(7)[..2], [(5, 5), (6, 4)]);
+        assert_eq!(
+            Regions::from_text(&LAYOUT.replacen('2', "0", 1)),
+            Err(Error::Parse {
+                line: 1,
+                column: 5,
+                token: "0".to_string()
+            })
+        );
+        assert_eq!(
+            Regions::from_text(&LAYOUT.replacen("1122", "1222", 1)),
+            Err(Error::RegionSize {
+                region: 1,
+                cells: 8
+            })
+        );
+        let mut swapped: Vec<char> = LAYOUT.chars().collect();
+        let last = swapped.len() - 2;
+        swapped.swap(0, last);
+        let swapped: String = swapped.into_iter().collect();
+        assert_eq!(
+            Regions::from_text(&swapped),
+            Err(Error::RegionNotConnected { region: 1 })
+        );
+        assert_eq!(
+            format!("{}", Error::RegionNotConnected { region: 1 }),
+            "region 1 is not connected"
+        );
+    }
+
+    #[test]
+    fn regions_map_errors() {
+        let parse = |line, column, token: &str| {
+            Err(Error::Parse {
+                line,
+                column,
+                token: token.to_string(),
+            })
+        };
+        assert_eq!(Regions::new(&[]), parse(1, 1, ""));
+        let square = vec![
+            vec![0, 0, 1, 1],
+            vec![0, 0, 1, 1],
+            vec![2, 2, 3, 3],
+            vec![2, 2, 3, 3],
+        ];
+        assert!(Regions::new(&square).is_ok());
+        let mut short = square.clone();
+        short[2].pop();
+        assert_eq!(Regions::new(&short), parse(3, 4, ""));
+        let mut long = square.clone();
+        long[1].push(3);
+        assert_eq!(Regions::new(&long), parse(2, 5, "3"));
+        let mut label = square.clone();
+        label[3][0] = 4;
+        assert_eq!(Regions::new(&label), parse(4, 1, "4"));
+        assert_eq!(
+            Regions::new(&[vec![0, 0], vec![0, 1]]),
+            Err(Error::RegionSize {
+                region: 1,
+                cells: 3
+            })
+        );
+    }
+
+    #[test]
+    fn jigsaw_solvers_work() {
+        let a = jigsaw(PUZZLE);
+        let b = jigsaw(SOLUTION);
+        assert!(b.solved());
+        assert_eq!(count_solutions(&a, 2), 1);
+        // Plain DFS takes long on so few clues, every third cell is given.
+        let easier: String = SOLUTION
+            .chars()
+            .zip(PUZZLE.chars())
+            .enumerate()
+            .map(|(i, (s, p))| if i % 3 == 0 { s } else { p })
+            .collect();
+        assert_eq!(solve_dfs_single(jigsaw(&easier)).unwrap(), b);
+        assert_eq!(solve_propagating_dfs(a.clone()).unwrap(), b);
+        assert_eq!(solve_dlx(a.clone()).unwrap(), b);
+        let w = solve_logic(&a).unwrap();
+        assert!(w.solved());
+        assert_eq!(w.table, b);
+        assert!(rate(&a).unwrap().solved);
+    }
+
+    #[test]
+    fn jigsaw_lines_work() {
+        let regions = Regions::from_text(LAYOUT).unwrap();
+        let a = Table::from_line_with_regions(PUZZLE, &regions).unwrap();
+        assert_eq!(a, jigsaw(PUZZLE));
+        // r1c4 shares the first region with r1c1, not the second box.
+        let mut g = Candidates::new(&a).unwrap();
+        g.place(0, 0, 5);
+        assert!(!g.can_place(0, 3, 5));
+        let puzzles = puzzles_from_lines_with_regions(&format!("{}\n{}\n", PUZZLE, "."), &regions);
+        assert_eq!(puzzles[0].1, Ok(a));
+        assert!(puzzles[1].1.is_err());
+        let small = Regions::from_text("1122\n1122\n3344\n3344").unwrap();
+        assert_eq!(
+            Table::from_line_with_regions(PUZZLE, &small).unwrap_err(),
+            Error::Parse {
+                line: 1,
+                column: 13,
+                token: "6".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn jigsaw_checks_and_annealer_work() {
+        let mut a = jigsaw(SOLUTION);
+        assert_eq!(a.cells_of(Unit::Group(0))[3], (0, 3));
+        // 3 is at r1c4 in the first region.
+        a.grid[1][8] = Cell::Empty;
+        a.grid[8][0] = Cell::Empty;
+        a.grid[1][0] = Cell::Digit(3);
+        assert!(!a.region_is_ok(0));
+        assert!(a.row_is_ok(1) && a.col_is_ok(0));
+        let conflicts = a.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].unit, Unit::Group(0));
+        a.grid[1][0] = Cell::Empty;
+        for i in 1..9 {
+            a.grid[i][(2 * i) % 9] = Cell::Empty;
+        }
+        assert_eq!(solve_simulated_annealing_single(a), Ok(jigsaw(SOLUTION)));
+    }
+}
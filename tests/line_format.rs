@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod tests {
+    use sudoku_solver_ed::app::puzzles_from_lines;
+    use sudoku_solver_ed::grid::{Cell, Table};
+
+    #[test]
+    fn from_line_works() {
+        let a = Table::from_line(
+            "435269781682571493197834562826195347374682915951743628519326874248957136763418259",
+        )
+        .unwrap();
+        assert!(a.solved());
+        let b = Table::from_line(
+            "4.0269781682571493197834562826195347374682915951743628519326874248957136763418259",
+        )
+        .unwrap();
+        assert_eq!(b.grid[0][1], Cell::Empty);
+        assert_eq!(b.grid[0][2], Cell::Empty);
+        assert_eq!(
+            b.to_line(),
+            "4..269781682571493197834562826195347374682915951743628519326874248957136763418259"
+        );
+        assert_eq!(Table::from_line(&b.to_line()).unwrap(), b);
+        assert!(Table::from_line("123").is_err());
+        assert!(Table::from_line(&"x".repeat(81)).is_err());
+    }
+
+    #[test]
+    fn puzzles_from_lines_works() {
+        let input = format!("# comment\n\n{}\nbad line\n", ".".repeat(81));
+        let puzzles = puzzles_from_lines(&input);
+        assert_eq!(puzzles.len(), 2);
+        assert_eq!(puzzles[0].0, 3);
+        assert_eq!(puzzles[0].1, Ok(Table::default()));
+        assert_eq!(puzzles[1].0, 4);
+        assert!(puzzles[1].1.is_err());
+    }
+}
@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod tests {
+    use sudoku_solver_ed::grid::{Candidates, Table};
+    use sudoku_solver_ed::solver::logic::{solve_logic, LogicSolver, Technique};
+
+    #[test]
+    fn logic_solves_easy_with_singles() {
+        let a = Table::from_line(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        let w = LogicSolver::with_techniques(&[Technique::HiddenSingle, Technique::NakedSingle])
+            .solve(&a)
+            .unwrap();
+        assert!(w.solved());
+        assert!(w.table.solved());
+        assert_eq!(w.steps.len(), a.empty_cells().len());
+        assert!(w.steps.iter().all(|s| s.placements.len() == 1));
+    }
+
+    #[test]
+    fn logic_finds_pointing() {
+        let mut g = Candidates::new(&Table::default()).unwrap();
+        for (r, c) in [(1, 0), (1, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+            g.eliminate(r, c, 1);
+        }
+        let step = LogicSolver::with_techniques(&[Technique::Pointing])
+            .next_step(&g)
+            .unwrap();
+        assert_eq!(step.technique, Technique::Pointing);
+        assert_eq!(step.cells, vec![(0, 0), (0, 1), (0, 2)]);
+        assert_eq!(
+            step.eliminations,
+            (3..9).map(|c| (0, c, 1)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            step.description,
+            "Pointing: 1 in group 1 only fits in row 1, which removes 1 from r1c4, r1c5, r1c6, r1c7, r1c8, r1c9"
+        );
+    }
+
+    #[test]
+    fn logic_steps_replay() {
+        let a = Table::from_line(
+            "..53.....8......2..7..1.5..4....53...1..7...6..32...8..6.5....9..4....3......97..",
+        )
+        .unwrap();
+        let w = solve_logic(&a).unwrap();
+        assert!(w.steps.iter().any(|s| s.technique == Technique::HiddenPair));
+        let mut g = Candidates::new(&a).unwrap();
+        for step in w.steps.iter() {
+            assert!(step.placements.len() + step.eliminations.len() > 0);
+            assert!(step.description.starts_with(step.technique.name()));
+            step.apply(&mut g);
+        }
+        assert_eq!(g.to_table(), w.table);
+    }
+}
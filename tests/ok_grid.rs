@@ -1,3 +1,5 @@
+#![allow(clippy::needless_range_loop)]
+
 #[cfg(test)]
 mod tests {
     use sudoku_solver_ed::grid::{Cell, Table};
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use sudoku_solver_ed::grid::{Cell, Table};
+    use sudoku_solver_ed::solver::{solve_dfs_single, solve_propagating_dfs};
+
+    #[test]
+    fn ensure_propagating_dfs() {
+        let a = Table::from_line(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        assert_eq!(
+            solve_propagating_dfs(a.clone()).unwrap(),
+            solve_dfs_single(a).unwrap()
+        );
+    }
+
+    #[test]
+    fn propagating_dfs_solves_hard_puzzle() {
+        let a = Table::from_line(
+            "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......",
+        )
+        .unwrap();
+        let res = solve_propagating_dfs(a.clone()).unwrap();
+        assert!(res.solved());
+        for r in 0..9 {
+            for c in 0..9 {
+                if a.grid[r][c] != Cell::Empty {
+                    assert_eq!(a.grid[r][c], res.grid[r][c]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn propagating_dfs_detects_no_solution() {
+        let a = Table::from_line(
+            "..........82561493.97834562.26195347.74682915.51743628.19326874.48957136.63418259",
+        )
+        .unwrap();
+        assert!(solve_propagating_dfs(a).is_err());
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod tests {
+    use sudoku_solver_ed::error::Error;
+    use sudoku_solver_ed::grid::{Cell, Table};
+    use sudoku_solver_ed::rating::{rate, Difficulty, EASIEST};
+    use sudoku_solver_ed::solver::logic::Technique;
+
+    #[test]
+    fn rate_works() {
+        let easy = Table::from_line(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        let r = rate(&easy).unwrap();
+        assert!(r.solved);
+        assert!(r.score <= 2.3);
+        assert_eq!(r.difficulty, Difficulty::Easy);
+
+        let hard = Table::from_line(
+            ".524.........7.1..............8.2...3.....6...9.5.....1.6.3...........897........",
+        )
+        .unwrap();
+        let r = rate(&hard).unwrap();
+        assert!(r.score >= 6.5 && r.score < 8.0);
+        assert_eq!(r.difficulty, Difficulty::Expert);
+        assert!(r.hardest >= Some(Technique::XChain));
+        assert!(format!("{}", r).ends_with(" (expert)"));
+    }
+
+    #[test]
+    fn rate_needs_unique_puzzle() {
+        let mut two = Table::from_line(
+            "435269781682571493197834562826195347374682915951743628519326874248957136763418259",
+        )
+        .unwrap();
+        assert_eq!(rate(&two).unwrap().score, EASIEST);
+        for (r, c) in [(0, 2), (0, 3), (1, 2), (1, 3)] {
+            two.grid[r][c] = Cell::Empty;
+        }
+        assert_eq!(rate(&two), Err(Error::NotUnique));
+    }
+}
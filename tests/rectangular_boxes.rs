@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod tests {
+    use sudoku_solver_ed::app::{parse_shape, puzzles_from_lines_with_shape};
+    use sudoku_solver_ed::grid::{Cell, Shape, Table, Unit};
+    use sudoku_solver_ed::rating::rate;
+    use sudoku_solver_ed::solver::dlx::solve_dlx;
+    use sudoku_solver_ed::solver::logic::{solve_logic, Technique};
+    use sudoku_solver_ed::solver::{count_solutions, solve_dfs_single, solve_propagating_dfs};
+
+    #[test]
+    fn shapes_work() {
+        assert_eq!(Shape::for_size(6), Some(Shape::new(2, 3)));
+        assert_eq!(Shape::for_size(12), Some(Shape::new(3, 4)));
+        assert_eq!(Shape::for_size(9), Some(Shape::square(3)));
+        assert_eq!(Shape::for_size(7), None);
+        let shape = Shape::new(2, 3);
+        assert_eq!(shape.group_of(1, 2), 0);
+        assert_eq!(shape.group_of(1, 3), 1);
+        assert_eq!(shape.group_of(2, 0), 2);
+        assert_eq!(
+            shape.group_cells(3).collect::<Vec<_>>(),
+            vec![(2, 3), (2, 4), (2, 5), (3, 3), (3, 4), (3, 5)]
+        );
+    }
+
+    #[test]
+    fn six_by_six_works() {
+        let a = Table::from_line("..6.4....6....4.5.....2.2....31.....").unwrap();
+        assert_eq!(a.shape(), Shape::new(2, 3));
+        assert_eq!(count_solutions(&a, 2), 1);
+        let b = solve_dfs_single(a.clone()).unwrap();
+        assert_eq!(b.to_line(), "536241412635324156651324265413143562");
+        assert!(b.solved());
+        for gr in 0..3 {
+            for gc in 0..2 {
+                assert!(b.group_is_ok(gr, gc));
+            }
+        }
+        assert_eq!(solve_propagating_dfs(a.clone()).unwrap(), b);
+        assert_eq!(solve_dlx(a).unwrap(), b);
+
+        // Apart in 3x3 boxes, together in 2x3 ones.
+        let mut c = Table::with_shape(Shape::new(2, 3));
+        c.grid[2][0] = Cell::Digit(1);
+        c.grid[3][2] = Cell::Digit(1);
+        assert_eq!(c.units().len(), 18);
+        assert_eq!(
+            c.cells_of(Unit::Group(2)),
+            vec![(2, 0), (2, 1), (2, 2), (3, 0), (3, 1), (3, 2)]
+        );
+        let conflicts = c.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].unit, Unit::Group(2));
+    }
+
+    #[test]
+    fn tall_boxes_work() {
+        let mut a = Table::from_arr([
+            [1, 2, 3, 4, 5, 6],
+            [3, 4, 5, 6, 1, 2],
+            [5, 6, 1, 2, 3, 4],
+            [2, 1, 4, 3, 6, 5],
+            [4, 3, 6, 5, 2, 1],
+            [6, 5, 2, 1, 4, 3],
+        ]);
+        assert!(!a.solved());
+        a.set_shape(Shape::new(3, 2));
+        assert!(a.solved());
+        assert_eq!(a.cells_of(Unit::Group(1))[..3], [(0, 2), (0, 3), (1, 2)]);
+        a.grid[0][0] = Cell::Empty;
+        a.grid[4][1] = Cell::Empty;
+        let b = solve_dlx(a.clone()).unwrap();
+        assert_eq!(b.shape(), Shape::new(3, 2));
+        assert_eq!(b.grid[0][0], Cell::Digit(1));
+        assert!(b.solved());
+    }
+
+    #[test]
+    fn twelve_by_twelve_works() {
+        let shape = Shape::new(3, 4);
+        let mut b = Table::with_shape(shape);
+        for r in 0..12 {
+            for c in 0..12 {
+                b.grid[r][c] = Cell::Digit(((r % 3) * 4 + r / 3 + c) as u8 % 12 + 1);
+            }
+        }
+        assert!(b.solved());
+        assert_eq!(Table::from_line(&b.to_line()), Ok(b.clone()));
+        let mut a = b.clone();
+        for i in 0..12 {
+            a.grid[i][(5 * i) % 12] = Cell::Empty;
+        }
+        assert_eq!(solve_propagating_dfs(a.clone()).unwrap(), b);
+        assert_eq!(solve_dlx(a).unwrap(), b);
+    }
+
+    #[test]
+    fn logic_works() {
+        let a = Table::from_line(".4....5....23.41........1...6..5..3.").unwrap();
+        let w = solve_logic(&a).unwrap();
+        assert!(w.solved());
+        assert_eq!(w.table, solve_dlx(a.clone()).unwrap());
+        assert!(w.techniques().contains(&Technique::XYChain));
+        let rating = rate(&a).unwrap();
+        assert!(rating.solved);
+        assert_eq!(rating.hardest, Some(Technique::XYChain));
+
+        // The same puzzle turned on its side.
+        let mut b = Table::with_shape(Shape::new(3, 2));
+        for r in 0..6 {
+            for c in 0..6 {
+                b.grid[r][c] = a.grid[c][r];
+            }
+        }
+        let w = solve_logic(&b).unwrap();
+        assert!(w.solved());
+        assert_eq!(w.table, solve_dlx(b).unwrap());
+    }
+
+    #[test]
+    fn lines_keep_the_shape() {
+        let shape = Shape::new(3, 2);
+        let mut a = Table::with_shape(shape);
+        a.grid[0][0] = Cell::Digit(1);
+        a.grid[2][1] = Cell::Digit(6);
+        let line = a.to_line();
+        assert_eq!(Table::from_line(&line).unwrap().shape(), Shape::new(2, 3));
+        assert_eq!(Table::from_line_with_shape(&line, shape), Ok(a.clone()));
+        let puzzles = puzzles_from_lines_with_shape(&format!("{}\n{}\n", line, "."), shape);
+        assert_eq!(puzzles[0].1, Ok(a));
+        assert!(puzzles[1].1.is_err());
+
+        let shape = Shape::new(4, 3);
+        let mut b = Table::with_shape(shape);
+        b.grid[3][2] = Cell::Digit(12);
+        let b2 = Table::from_line_with_shape(&b.to_line(), shape).unwrap();
+        assert_eq!(b2.shape(), shape);
+        assert_eq!(b2.to_line(), b.to_line());
+        assert!(Table::from_line_with_shape(&".".repeat(81), shape).is_err());
+
+        assert_eq!(parse_shape("3x2"), Some(Shape::new(3, 2)));
+        assert_eq!(parse_shape("4x3"), Some(shape));
+        assert_eq!(parse_shape("1x6"), None);
+        assert_eq!(parse_shape("3"), None);
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use sudoku_solver_ed::grid::{Cell, Table};
+    use sudoku_solver_ed::solver::{count_solutions, solutions, solve_dfs_single};
+
+    const SOLUTION: &str =
+        "435269781682571493197834562826195347374682915951743628519326874248957136763418259";
+
+    #[test]
+    fn solutions_yields_every_completion() {
+        let full = Table::from_line(SOLUTION).unwrap();
+        assert_eq!(
+            solutions(full.clone()).collect::<Vec<_>>(),
+            vec![full.clone()]
+        );
+
+        let mut a = full.clone();
+        a.grid[0][2] = Cell::Empty;
+        a.grid[0][3] = Cell::Empty;
+        a.grid[1][2] = Cell::Empty;
+        a.grid[1][3] = Cell::Empty;
+        let all: Vec<Table> = solutions(a.clone()).collect();
+        assert_eq!(all.len(), 2);
+        assert!(all.contains(&full));
+        assert!(all.iter().all(|t| t.solved()));
+        assert_eq!(all[0], solve_dfs_single(a).unwrap());
+
+        let mut b = Table::from_line(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        for c in 0..9 {
+            b.grid[8][c] = Cell::Empty;
+            b.grid[7][c] = Cell::Empty;
+        }
+        assert_eq!(solutions(b.clone()).count(), count_solutions(&b, 1000));
+    }
+
+    #[test]
+    fn solutions_is_lazy() {
+        let mut it = solutions(Table::default());
+        let first = it.next().unwrap();
+        let second = it.next().unwrap();
+        assert!(first.solved() && second.solved());
+        assert_ne!(first, second);
+        assert_eq!(solutions(Table::default()).take(50).count(), 50);
+    }
+
+    #[test]
+    fn solutions_of_invalid_table_is_empty() {
+        let mut a = Table::default();
+        a.grid[0][0] = Cell::Digit(7);
+        a.grid[0][8] = Cell::Digit(7);
+        assert_eq!(solutions(a).next(), None);
+        let b = Table::from_line(
+            "..........82561493.97834562.26195347.74682915.51743628.19326874.48957136.63418259",
+        )
+        .unwrap();
+        assert_eq!(solutions(b).next(), None);
+    }
+}
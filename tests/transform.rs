@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use sudoku_solver_ed::error::Error;
+    use sudoku_solver_ed::grid::{Cell, Table};
+    use sudoku_solver_ed::transform::Transform;
+
+    fn solution() -> Table {
+        Table::from_line(
+            "435269781682571493197834562826195347374682915951743628519326874248957136763418259",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn transforms_work() {
+        let a = solution();
+        let r = Transform::rotate().apply(&a).unwrap();
+        assert!(r.solved());
+        assert_eq!(r.grid[0][8], a.grid[0][0]);
+        assert_eq!(r.grid[8][8], a.grid[0][8]);
+        let full_turn = (0..4).fold(Transform::identity(), |t, _| t.then(&Transform::rotate()));
+        assert_eq!(full_turn, Transform::identity());
+
+        assert_eq!(
+            Transform::swap_rows(3, 5).apply(&a).unwrap().grid[3],
+            a.grid[5]
+        );
+        assert_eq!(
+            Transform::swap_bands(0, 2).apply(&a).unwrap().grid[7],
+            a.grid[1]
+        );
+        assert_eq!(
+            Transform::swap_stacks(0, 1).apply(&a).unwrap().grid[0][4],
+            a.grid[0][1]
+        );
+        let relabelled = Transform::relabel([9, 8, 7, 6, 5, 4, 3, 2, 1])
+            .apply(&a)
+            .unwrap();
+        assert_eq!(relabelled.grid[0][0], Cell::Digit(6));
+        assert!(relabelled.solved());
+    }
+
+    #[test]
+    fn transforms_compose_and_invert() {
+        let a = solution();
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..20 {
+            let x = Transform::random(&mut rng);
+            let y = Transform::random(&mut rng);
+            let b = x.apply(&a).unwrap();
+            assert!(b.solved());
+            assert_eq!(x.inverse().apply(&b).unwrap(), a);
+            assert_eq!(x.then(&y).apply(&a).unwrap(), y.apply(&b).unwrap());
+            assert_eq!(x.then(&x.inverse()), Transform::identity());
+            assert_eq!(Transform::from_line(&x.to_line()), Ok(x));
+        }
+    }
+
+    #[test]
+    fn transform_from_line_errors() {
+        assert_eq!(
+            Transform::identity().to_line(),
+            "n 123456789 123456789 123456789"
+        );
+        assert_eq!(
+            Transform::from_line("t 123456789 123456789 123456789"),
+            Ok(Transform::transpose())
+        );
+        assert_eq!(
+            Transform::from_line("n 124356789 123456789 123456789"),
+            Err(Error::Parse {
+                line: 1,
+                column: 3,
+                token: "124356789".to_string()
+            })
+        );
+        assert!(Transform::from_line("n 123456789 123456789").is_err());
+        assert!(Transform::from_line("n 123456789 123456789 123456788").is_err());
+    }
+}
@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod tests {
+    use sudoku_solver_ed::error::Error;
+    use sudoku_solver_ed::grid::{Candidates, Cell, Table};
+    use sudoku_solver_ed::solver::logic::{LogicSolver, Technique, Uniqueness};
+
+    #[test]
+    fn unique_rectangle_needs_uniqueness() {
+        let mut g = Candidates::new(&Table::default()).unwrap();
+        for (r, c) in [(0, 0), (0, 3), (1, 0), (1, 3)] {
+            for d in 3..=9 {
+                if (r, c) != (1, 3) || d != 5 {
+                    g.eliminate(r, c, d);
+                }
+            }
+        }
+        let mut solver = LogicSolver::with_techniques(&[Technique::UniqueRectangle1]);
+        assert!(solver.next_step(&g).is_none());
+        solver.set_uniqueness(Uniqueness::Assume);
+        let step = solver.next_step(&g).unwrap();
+        assert_eq!(step.technique, Technique::UniqueRectangle1);
+        assert_eq!(step.eliminations, vec![(1, 3, 1), (1, 3, 2)]);
+        assert_eq!(
+            step.description,
+            "Unique Rectangle type 1: 1/2 on r1c1, r1c4, r2c1, r2c4 would be deadly without \
+             the extras in r2c4, which removes 1 from r2c4 and 2 from r2c4"
+        );
+    }
+
+    #[test]
+    fn verify_uniqueness_works() {
+        let solution = Table::from_line(
+            "435269781682571493197834562826195347374682915951743628519326874248957136763418259",
+        )
+        .unwrap();
+        let mut solver = LogicSolver::new();
+        solver.set_uniqueness(Uniqueness::Verify);
+        assert!(solver.solve(&solution).unwrap().solved());
+
+        let mut two = solution.clone();
+        for (r, c) in [(0, 2), (0, 3), (1, 2), (1, 3)] {
+            two.grid[r][c] = Cell::Empty;
+        }
+        assert_eq!(solver.solve(&two), Err(Error::NotUnique));
+        solver.set_uniqueness(Uniqueness::Assume);
+        assert!(!solver.solve(&two).unwrap().solved());
+
+        let none = Table::from_line(
+            "..........82561493.97834562.26195347.74682915.51743628.19326874.48957136.63418259",
+        )
+        .unwrap();
+        solver.set_uniqueness(Uniqueness::Verify);
+        assert_eq!(solver.solve(&none), Err(Error::NoSolution));
+    }
+}